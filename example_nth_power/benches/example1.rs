@@ -1,6 +1,7 @@
 use example::example1::TestCircuit;
 use halo2_proofs::{
-    pasta::{vesta, Fp},
+    arithmetic::CurveAffine,
+    pasta::{pallas, vesta},
     plonk::*,
     poly::commitment::Params,
     transcript::{Blake2bRead, Blake2bWrite, Challenge255},
@@ -11,14 +12,14 @@ use std::marker::PhantomData;
 use criterion::{criterion_group, criterion_main, Criterion};
 use rand::rngs::OsRng;
 
-// K is the dimension for the poly commit
-fn bench_example<const K: u32>(name: &str, c: &mut Criterion) {
+// K is the dimension for the poly commit, C the curve committing to vectors over its scalar field
+fn bench_example<C: CurveAffine, const K: u32>(name: &str, c: &mut Criterion) {
     // Set the polynomial commitment parameters
     let mut rng = OsRng;
-    let params: Params<vesta::Affine> = Params::new(K);
+    let params: Params<C> = Params::new(K);
 
     // Define a circuit
-    let circuit = TestCircuit(PhantomData);
+    let circuit = TestCircuit::<C::Scalar>(PhantomData);
 
     // Set the verifier and prover key according to the params and circuit
     let vk = keygen_vk(&params, &circuit).expect("vk generation failed");
@@ -28,8 +29,8 @@ fn bench_example<const K: u32>(name: &str, c: &mut Criterion) {
     let verifier_name = "Measure verifier time in ".to_owned() + name;
 
     // Set the instances
-    let input = Fp::from(2); // input
-    let output = Fp::from(4096); // expected result y
+    let input = C::Scalar::from(2); // input
+    let output = C::Scalar::from(4096); // expected result y
     let public_input = [input, output];
 
     // Benchmarking proof gereration time
@@ -67,7 +68,8 @@ fn bench_example<const K: u32>(name: &str, c: &mut Criterion) {
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
-    bench_example::<7>("example1", c);
+    bench_example::<vesta::Affine, 7>("example1", c);
+    bench_example::<pallas::Affine, 7>("example1 over pallas", c);
 }
 
 criterion_group!(benches, criterion_benchmark);
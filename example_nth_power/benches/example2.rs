@@ -1,98 +1,118 @@
-use halo2::poly::VerificationStrategy;
+use halo2::plonk::*;
 use halo2::{
+    circuit::Value,
     halo2curves::bn256::{Bn256, Fr, G1Affine},
-    poly::commitment::Params,
-    poly::{
-        commitment::ParamsProver,
-        kzg::{
-            commitment::{KZGCommitmentScheme, ParamsKZG},
-            multiopen::{ProverGWC, VerifierGWC},
-            strategy::AccumulatorStrategy,
-        },
+    poly::kzg::{
+        commitment::{KZGCommitmentScheme, ParamsKZG},
+        multiopen::ProverGWC,
     },
-    transcript::{Blake2bRead, Blake2bWrite, Challenge255},
-    transcript::{TranscriptReadBuffer, TranscriptWriterBuffer},
+    transcript::{Blake2bWrite, Challenge255, TranscriptWriterBuffer},
 };
-use halo2::{plonk::*, SerdeFormat};
 // use halo2curves::pasta::{EqAffine, Fr};
+use rayon::prelude::*;
 use std::{
-    fs::File,
-    io::{BufReader, Read, Write},
-    marker::PhantomData,
+    env,
     path::Path,
+    str::FromStr,
+    time::{Duration, Instant},
 };
 // bench-mark tool
-use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, SamplingMode};
-use example::example2::TestCircuit;
+use criterion::{
+    criterion_group, criterion_main, BenchmarkId, Criterion, SamplingMode, Throughput,
+};
+use example::constant_base::ConstantBaseCircuit;
+use example::dry_run::dry_run;
+use example::example2::{
+    assert_instances_consistent, compute_output, min_k, usable_rows, TestCircuit,
+};
+use example::persistence::{
+    get_or_create_params, load_pk_tagged, load_proof_tagged, load_vk_tagged, save_pk_tagged,
+    save_proof_tagged, save_vk_tagged, CIRCUIT_VERSION,
+};
+use example::prove::prove_batch;
+use example::square_multiply::SquareMultiplyCircuit;
+use example::verify::{verify_batch, verify_power_proof};
 use rand::rngs::OsRng;
 
-// K is the dimension for the poly commit
-fn bench_example(k: u32, name: &str, c: &mut Criterion) {
-    // Set the polynomial commitment parameters
-    let params_path = Path::new("./benches/data/params_example2");
-    if File::open(params_path).is_err() {
-        let params = ParamsKZG::<Bn256>::setup(k, OsRng);
-        let mut buf = Vec::new();
+// Reads a bench parameter from the environment, falling back to `default` when it is unset.
+// A set but malformed value panics, as in `TestParams::from_env`: a typo should not silently
+// bench the defaults.
+fn env_or<T: FromStr>(name: &str, default: T) -> T {
+    match env::var(name) {
+        Ok(value) => value
+            .parse()
+            .unwrap_or_else(|_| panic!("invalid {} `{}`", name, value)),
+        Err(_) => default,
+    }
+}
 
-        params.write(&mut buf).expect("Failed to write params");
-        let mut file = File::create(params_path).expect("Failed to create params");
+// Cached artifacts carry a k, circuit version and params tag, and are regenerated when it does
+// not match.
+// Params are shared with the tests through `get_or_create_params`.
+fn cached_params(k: u32) -> ParamsKZG<Bn256> {
+    get_or_create_params(k)
+}
 
-        file.write_all(&buf[..])
-            .expect("Failed to write params to file");
+// Keys are keyed by k and exp, the exponent being structural
+fn cached_vk(params: &ParamsKZG<Bn256>, k: u32, exp: usize) -> VerifyingKey<G1Affine> {
+    let vk_path = format!(
+        "./benches/data/vk_example2_k{}_exp{}_v{}",
+        k, exp, CIRCUIT_VERSION
+    );
+    match load_vk_tagged::<TestCircuit<Fr>>(&vk_path, params) {
+        Ok(vk) => vk,
+        Err(_) => {
+            let vk = keygen_vk(params, &TestCircuit::<Fr>::new(exp)).expect("keygen_vk failed");
+            save_vk_tagged(&vk_path, params, &vk).expect("Failed to write vk");
+            vk
+        }
     }
-    let params_fs = File::open(params_path).expect("Failed to load params");
-    let params: ParamsKZG<Bn256> =
-        ParamsKZG::read::<_>(&mut BufReader::new(params_fs)).expect("Failed to read params");
+}
+
+fn cached_pk(params: &ParamsKZG<Bn256>, k: u32, exp: usize) -> ProvingKey<G1Affine> {
+    let pk_path = format!(
+        "./benches/data/pk_example2_k{}_exp{}_v{}",
+        k, exp, CIRCUIT_VERSION
+    );
+    match load_pk_tagged::<TestCircuit<Fr>>(&pk_path, params) {
+        Ok(pk) => pk,
+        Err(_) => {
+            let circuit = TestCircuit::<Fr>::new(exp);
+            let vk = cached_vk(params, k, exp);
+            let pk = keygen_pk(params, vk, &circuit).expect("keygen_pk failed");
+            save_pk_tagged(&pk_path, params, &pk).expect("Failed to write pk");
+            pk
+        }
+    }
+}
+
+// K is the dimension for the poly commit, exp fixes the circuit
+fn bench_example(k: u32, base: u64, exp: usize, name: &str, c: &mut Criterion) {
+    // Set the polynomial commitment parameters
+    let params = cached_params(k);
 
     // Define a circuit
-    let circuit = TestCircuit(PhantomData);
+    let circuit = TestCircuit::new(exp);
 
     let prover_name = "Measure prover time in ".to_owned() + name;
     let verifier_name = "Measure verifier time in ".to_owned() + name;
 
     // Set the instances
-    let input = Fr::from(2); // input
-    let output = Fr::from(4); // expected result y
+    let input = Fr::from(base); // input
+    let output = compute_output(input, circuit.exp); // expected result y
 
     let public_input = [input, output];
+    assert_instances_consistent(input, exp, output);
 
-    // write verifying key
-    let vk_path = "./benches/data/vk_example2";
-    if File::open(&vk_path).is_err() {
-        let vk = keygen_vk(&params, &circuit.clone()).expect("keygen_vk failed");
-        let mut buf = Vec::new();
-        let _ = vk.write(&mut buf, SerdeFormat::RawBytes);
-        let mut file = File::create(&vk_path).expect("Failed to create vk");
-        file.write_all(&buf[..])
-            .expect("Failed to write vk to file");
-    }
-    let vk_fs = File::open(vk_path).expect("Failed to load vk");
-    let vk = VerifyingKey::<G1Affine>::read::<BufReader<File>, TestCircuit<Fr>>(
-        &mut BufReader::new(vk_fs),
-        SerdeFormat::RawBytes,
-    )
-    .expect("Failed to read vk");
-
-    // write proving key
-    let pk_path = "./benches/data/pk_example2";
-    if File::open(&pk_path).is_err() {
-        let pk = keygen_pk(&params, vk, &circuit.clone()).expect("keygen_pk failed");
-        let mut buf = Vec::new();
-        let _ = pk.write(&mut buf, SerdeFormat::RawBytes);
-        let mut file = File::create(&pk_path).expect("Failed to create pk");
-        file.write_all(&buf[..])
-            .expect("Failed to write pk to file");
-    }
-    let pk_fs = File::open(pk_path).expect("Failed to load pk");
-    let pk = ProvingKey::<G1Affine>::read::<BufReader<File>, TestCircuit<Fr>>(
-        &mut BufReader::new(pk_fs),
-        SerdeFormat::RawBytes,
-    )
-    .expect("Failed to read pk");
+    let pk = cached_pk(&params, k, exp);
 
-    // Create a proof
-    let proof_path = Path::new("./benches/data/proof_example2");
-    if File::open(proof_path).is_err() {
+    // Create a proof, unless one made with these params and this circuit version is cached
+    let proof_path = format!(
+        "./benches/data/proof_example2_k{}_exp{}_base{}_v{}",
+        k, exp, base, CIRCUIT_VERSION
+    );
+    let proof_path = Path::new(&proof_path);
+    if load_proof_tagged(proof_path, &params).is_err() {
         let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
         c.bench_function(&prover_name, |b| {
             b.iter(|| {
@@ -108,45 +128,302 @@ fn bench_example(k: u32, name: &str, c: &mut Criterion) {
             })
         });
         let proof: Vec<u8> = transcript.finalize();
-        let mut file = File::create(proof_path).expect("Failed to create proof");
-        file.write_all(&proof[..]).expect("Failed to write proof");
+        save_proof_tagged(proof_path, &params, &proof).expect("Failed to write proof");
     }
 
-    let mut proof_fs = File::open(proof_path).expect("Failed to load proof");
-    let mut proof = Vec::<u8>::new();
-    proof_fs
-        .read_to_end(&mut proof)
-        .expect("Couldn't read proof");
+    let proof = load_proof_tagged(proof_path, &params).expect("Couldn't read proof");
 
-    // verify the proof
+    // verify the proof, the verifier only holds the vk read back from its file
+    drop(pk);
+    let vk = cached_vk(&params, k, exp);
     c.bench_function(&verifier_name, |b| {
         b.iter(|| {
-            let accept = {
-                let mut transcript: Blake2bRead<&[u8], _, Challenge255<_>> =
-                    TranscriptReadBuffer::<_, G1Affine, _>::init(proof.as_slice());
-                VerificationStrategy::<_, VerifierGWC<_>>::finalize(
-                    verify_proof::<_, VerifierGWC<_>, _, _, _>(
-                        params.verifier_params(),
-                        pk.get_vk(),
-                        AccumulatorStrategy::new(params.verifier_params()),
-                        &[&[&public_input]],
-                        &mut transcript,
-                    )
-                    .unwrap(),
-                )
-            };
+            let accept = verify_power_proof(&params, &vk, &public_input, &proof)
+                .expect("proof verification failed");
             assert!(accept);
         });
     });
 }
 
+// Proves `n` independent statements at once, one transcript per proof and per thread.
+// Criterion reports the throughput in proofs per second; the wall-clock time of a round and
+// the latency of a single proof within it are printed once beforehand.
+fn bench_parallel(k: u32, base: u64, exp: usize, n: usize, name: &str, c: &mut Criterion) {
+    let params = cached_params(k);
+    let pk = cached_pk(&params, k, exp);
+    let circuit = TestCircuit::<Fr>::new(exp);
+
+    let statements: Vec<[Fr; 2]> = (0..n as u64)
+        .map(|i| {
+            let input = Fr::from(base + i);
+            [input, compute_output(input, exp)]
+        })
+        .collect();
+    for [input, output] in &statements {
+        assert_instances_consistent(*input, exp, *output);
+    }
+
+    let prove_all = || -> Vec<(Vec<u8>, Duration)> {
+        statements
+            .par_iter()
+            .map(|public_input| {
+                let start = Instant::now();
+                let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+                create_proof::<KZGCommitmentScheme<_>, ProverGWC<_>, _, _, _, _>(
+                    &params,
+                    &pk,
+                    &[circuit.clone()],
+                    &[&[public_input.as_slice()]],
+                    OsRng,
+                    &mut transcript,
+                )
+                .expect("proof generation failed");
+                (transcript.finalize(), start.elapsed())
+            })
+            .collect()
+    };
+
+    let start = Instant::now();
+    let proofs = prove_all();
+    let wall = start.elapsed();
+    let latency = proofs.iter().map(|(_, elapsed)| *elapsed).sum::<Duration>() / n as u32;
+    println!(
+        "{}: {} proofs on {} threads in {:?} ({:.2} proofs/s), {:?} per proof",
+        name,
+        n,
+        rayon::current_num_threads(),
+        wall,
+        n as f64 / wall.as_secs_f64(),
+        latency
+    );
+
+    // every proof of the round stands on its own
+    for (public_input, (proof, _)) in statements.iter().zip(&proofs) {
+        let accept = verify_power_proof(&params, pk.get_vk(), public_input, proof)
+            .expect("proof verification failed");
+        assert!(accept);
+    }
+
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(n as u64));
+    group.bench_function(BenchmarkId::new("parallel prover", n), |b| {
+        b.iter(prove_all)
+    });
+    group.finish();
+}
+
+// Setup cost, which the other benches hide behind the key cache. Nothing is read from or
+// written to ./benches/data, and the keys are dropped once measured.
+fn bench_keygen(k: u32, exp: usize, name: &str, c: &mut Criterion) {
+    let params = cached_params(k);
+    let circuit = TestCircuit::<Fr>::new(exp);
+
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+    group.bench_function(BenchmarkId::new("keygen_vk", exp), |b| {
+        b.iter(|| keygen_vk(&params, &circuit).expect("keygen_vk failed"))
+    });
+
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+    group.bench_function(BenchmarkId::new("keygen_pk", exp), |b| {
+        b.iter(|| keygen_pk(&params, vk.clone(), &circuit).expect("keygen_pk failed"))
+    });
+    group.finish();
+}
+
+fn prove<C: Circuit<Fr> + Clone>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    circuit: &C,
+    public_input: &[Fr],
+) -> Vec<u8> {
+    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+    create_proof::<KZGCommitmentScheme<_>, ProverGWC<_>, _, _, _, _>(
+        params,
+        pk,
+        &[circuit.clone()],
+        &[&[public_input]],
+        OsRng,
+        &mut transcript,
+    )
+    .expect("proof generation failed");
+    transcript.finalize()
+}
+
+// Proves the same x^exp with the O(exp) chain of example2 and the O(log exp) ladder of
+// square_multiply, each at the smallest k its rows fit in.
+fn bench_naive_vs_square_multiply(base: u64, exp: usize, name: &str, c: &mut Criterion) {
+    let input = Fr::from(base);
+    let output = compute_output(input, exp);
+    assert_instances_consistent(input, exp, output);
+
+    let naive_k = min_k(exp);
+    let naive_params = cached_params(naive_k);
+    let naive_pk = cached_pk(&naive_params, naive_k, exp);
+    let naive = TestCircuit::<Fr>::new(exp);
+    let naive_input = [input, output];
+
+    let num_bits = (usize::BITS - exp.leading_zeros()).max(1) as usize;
+    let ladder = SquareMultiplyCircuit::<Fr>::new(Value::known(exp as u64), num_bits);
+    let ladder_rows = dry_run(&ladder).expect("dry run failed").rows();
+    let ladder_k = (3..).find(|&k| usable_rows(k) >= ladder_rows).unwrap();
+    let ladder_params = cached_params(ladder_k);
+    let ladder_vk = keygen_vk(&ladder_params, &ladder).expect("keygen_vk failed");
+    let ladder_pk = keygen_pk(&ladder_params, ladder_vk, &ladder).expect("keygen_pk failed");
+    let ladder_input = [input, output, Fr::from(exp as u64)];
+
+    println!(
+        "{}: x^{} in {} rows at k = {} (naive) and {} rows at k = {} (square-and-multiply)",
+        name,
+        exp,
+        dry_run(&naive).expect("dry run failed").rows(),
+        naive_k,
+        ladder_rows,
+        ladder_k
+    );
+
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+    group.sampling_mode(SamplingMode::Flat);
+    group.bench_function(BenchmarkId::new("naive prover", exp), |b| {
+        b.iter(|| prove(&naive_params, &naive_pk, &naive, &naive_input))
+    });
+    group.bench_function(BenchmarkId::new("square-and-multiply prover", exp), |b| {
+        b.iter(|| prove(&ladder_params, &ladder_pk, &ladder, &ladder_input))
+    });
+    group.finish();
+}
+
+// Proves c^exp with the naive chain and with the chain folding up to four multiplications by
+// the constant c into a row, each at the smallest k its rows fit in.
+fn bench_naive_vs_constant_base(base: u64, exp: usize, name: &str, c: &mut Criterion) {
+    let input = Fr::from(base);
+    let output = compute_output(input, exp);
+
+    let naive_k = min_k(exp);
+    let naive_params = cached_params(naive_k);
+    let naive_pk = cached_pk(&naive_params, naive_k, exp);
+    let naive = TestCircuit::<Fr>::new(exp);
+    let naive_input = [input, output];
+
+    let folded = ConstantBaseCircuit::new(input, exp);
+    let folded_rows = dry_run(&folded).expect("dry run failed").rows();
+    let folded_k = (3..).find(|&k| usable_rows(k) >= folded_rows).unwrap();
+    let folded_params = cached_params(folded_k);
+    let folded_vk = keygen_vk(&folded_params, &folded).expect("keygen_vk failed");
+    let folded_pk = keygen_pk(&folded_params, folded_vk, &folded).expect("keygen_pk failed");
+    let folded_input = [output];
+
+    println!(
+        "{}: {}^{} in {} rows at k = {} (naive) and {} rows at k = {} (constant base)",
+        name,
+        base,
+        exp,
+        dry_run(&naive).expect("dry run failed").rows(),
+        naive_k,
+        folded_rows,
+        folded_k
+    );
+
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+    group.sampling_mode(SamplingMode::Flat);
+    group.bench_function(BenchmarkId::new("naive prover", exp), |b| {
+        b.iter(|| prove(&naive_params, &naive_pk, &naive, &naive_input))
+    });
+    group.bench_function(BenchmarkId::new("constant base prover", exp), |b| {
+        b.iter(|| prove(&folded_params, &folded_pk, &folded, &folded_input))
+    });
+    group.finish();
+}
+
+// Verifies `n` proofs of the same circuit one by one, each with its own pairing check, and as
+// one batch sharing a single final pairing.
+fn bench_batch_verify(k: u32, base: u64, exp: usize, n: usize, name: &str, c: &mut Criterion) {
+    let params = cached_params(k);
+    let pk = cached_pk(&params, k, exp);
+
+    let statements: Vec<(Fr, Fr)> = (0..n as u64)
+        .map(|i| {
+            let input = Fr::from(base + i);
+            (input, compute_output(input, exp))
+        })
+        .collect();
+    let proofs: Vec<Vec<u8>> = statements
+        .par_iter()
+        .map(|&(input, output)| {
+            prove_batch(&params, &pk, &[(input, output, exp)], OsRng)
+                .expect("proof generation failed")
+        })
+        .collect();
+
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(n as u64));
+    group.bench_function(BenchmarkId::new("per-proof verifier", n), |b| {
+        b.iter(|| {
+            for (&(input, output), proof) in statements.iter().zip(&proofs) {
+                let accept = verify_power_proof(&params, pk.get_vk(), &[input, output], proof)
+                    .expect("proof verification failed");
+                assert!(accept);
+            }
+        })
+    });
+    group.bench_function(BenchmarkId::new("batch verifier", n), |b| {
+        b.iter(|| assert!(verify_batch(&params, pk.get_vk(), &statements, &proofs)))
+    });
+    group.finish();
+}
+
 fn main() {
     let mut criterion = Criterion::default();
     // .sample_size(100)  // 샘플 크기 설정
     // .nresamples(100);  // 반복 횟수 설정
 
-    let benches: Vec<Box<dyn Fn(&mut Criterion)>> =
-        vec![Box::new(|c| bench_example(3, "example1", c))];
+    // e.g. `EXP=10 BASE=3 cargo bench --bench example2`; K defaults to the smallest fitting k
+    let exp = env_or("EXP", 2);
+    let base = env_or("BASE", 2);
+    let k = env_or("K", min_k(exp));
+    assert!(
+        k >= min_k(exp),
+        "K = {} is too small for EXP = {}, which needs at least k = {}",
+        k,
+        exp,
+        min_k(exp)
+    );
+
+    // N statements are proven concurrently by the parallel bench
+    let n = env_or("N", 8);
+
+    // BATCH proofs are verified one by one and as a batch
+    let batch = env_or("BATCH", 50);
+
+    // the exponent both chips are compared at, large enough for the ladder to pay off
+    let compare_exp = env_or("COMPARE_EXP", 1024);
+
+    // `BENCH_KEYGEN=1` or `cargo bench --bench example2 -- --bench-keygen` adds the uncached
+    // keygen benches
+    let bench_keygen_too =
+        env::var_os("BENCH_KEYGEN").is_some() || env::args().any(|arg| arg == "--bench-keygen");
+
+    let mut benches: Vec<Box<dyn Fn(&mut Criterion)>> = vec![
+        Box::new(move |c| bench_example(k, base, exp, "example1", c)),
+        Box::new(move |c| bench_parallel(k, base, exp, n, "example2 parallel", c)),
+        Box::new(move |c| bench_batch_verify(k, base, exp, batch, "example2 batch verify", c)),
+        Box::new(move |c| {
+            bench_naive_vs_square_multiply(base, compare_exp, "naive vs square-and-multiply", c)
+        }),
+        Box::new(move |c| {
+            bench_naive_vs_constant_base(base, compare_exp, "naive vs constant base", c)
+        }),
+    ];
+
+    if bench_keygen_too {
+        benches.push(Box::new(move |c| {
+            bench_keygen(k, exp, "example2 keygen", c)
+        }));
+    }
 
     for bench in benches {
         bench(&mut criterion);
@@ -0,0 +1,213 @@
+// Additive counterpart of the power chip in example2.
+// It proves the relation R = { ( x, y; n): x * n = y } by adding x to itself repeatedly,
+// using the same three-column layout but with the gate s * (a + b - c).
+use alloc::vec;
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct AddByNumConfig {
+    pub col_a: Column<Advice>,
+    pub col_b: Column<Advice>,
+    pub col_c: Column<Advice>,
+    pub selector: Selector,
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AddByNumChip<F: PrimeField> {
+    config: AddByNumConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> AddByNumChip<F> {
+    pub fn construct(config: AddByNumConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> AddByNumConfig {
+        let col_a = meta.advice_column();
+        let col_b = meta.advice_column();
+        let col_c = meta.advice_column();
+        let selector = meta.selector();
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        meta.enable_equality(col_a);
+        meta.enable_equality(col_b);
+        meta.enable_equality(col_c);
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate("add", |meta| {
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            vec![s * (a + b - c)]
+        });
+
+        AddByNumConfig {
+            col_a,
+            col_b,
+            col_c,
+            selector,
+            instance,
+            constant,
+        }
+    }
+
+    pub fn initial_assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "first region",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                // the additive identity plays the role of the `1` seed in the power chip
+                let init_a = region.assign_advice_from_constant(
+                    || "constant",
+                    self.config.col_a,
+                    0,
+                    F::from(0),
+                )?;
+
+                let init_b = region.assign_advice_from_instance(
+                    || "instance",
+                    self.config.instance,
+                    0,
+                    self.config.col_b,
+                    0,
+                )?;
+
+                let init_c = region.assign_advice(
+                    || "init_a + init_b",
+                    self.config.col_c,
+                    0,
+                    || init_a.value().copied() + init_b.value(),
+                )?;
+
+                Ok((init_a, init_b, init_c))
+            },
+        )
+    }
+
+    pub fn subsequent_assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        prev_b: &AssignedCell<F, F>,
+        prev_c: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "subsequent row",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                // copy the value from previous region
+                prev_c.copy_advice(|| "a", &mut region, self.config.col_a, 0)?;
+
+                prev_b.copy_advice(|| "b", &mut region, self.config.col_b, 0)?;
+
+                let res_c = region.assign_advice(
+                    || "c",
+                    self.config.col_c,
+                    0,
+                    || prev_b.value().copied() + prev_c.value(),
+                )?;
+
+                Ok(res_c)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+// `times` is structural like `exp` in example2: the input is added `times - 1` times to itself.
+#[derive(Default, Clone)]
+pub struct AddCircuit<F> {
+    pub times: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> AddCircuit<F> {
+    pub fn new(times: usize) -> Self {
+        Self {
+            times,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for AddCircuit<F> {
+    type Config = AddByNumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.times)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        AddByNumChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = AddByNumChip::construct(config);
+
+        let (_, prev_b, mut prev_c) = chip.initial_assign(layouter.namespace(|| "first region"))?;
+
+        for _i in 1..self.times {
+            prev_c = chip.subsequent_assign(
+                layouter.namespace(|| "subsequent region"),
+                &prev_b,
+                &prev_c,
+            )?;
+        }
+
+        chip.expose_public(layouter.namespace(|| "out"), &prev_c, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AddCircuit;
+    use halo2::{dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn add_test() {
+        let k = 4;
+
+        // 2 added to itself 4 times: 2 + 2 + 2 + 2 + 2 = 10
+        let input = Fr::from(2);
+        let output = Fr::from(10);
+
+        let circuit = AddCircuit::new(5);
+
+        let public_input = vec![input, output];
+
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+}
@@ -0,0 +1,221 @@
+// Lookup-based range check on the base of the example2 power circuit.
+// The base cell is copied into an advice column and looked up in a fixed table holding the
+// allowed domain, the digits 0..=9 or the bytes 0..=255, so a base outside of it cannot be
+// proven. The chip can be added to any circuit holding the base in a cell.
+use crate::example2::{ChainMode, PowerByNumChip, PowerByNumConfig};
+use alloc::vec;
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseDomain {
+    // 0..=9
+    Digit,
+    // 0..=255
+    Byte,
+}
+
+impl BaseDomain {
+    pub fn max(self) -> u64 {
+        match self {
+            BaseDomain::Digit => 9,
+            BaseDomain::Byte => 255,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BaseRangeConfig {
+    pub base: Column<Advice>,
+    pub selector: Selector,
+    pub table: TableColumn,
+}
+
+#[derive(Debug, Clone)]
+pub struct BaseRangeChip<F: PrimeField> {
+    config: BaseRangeConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> BaseRangeChip<F> {
+    pub fn construct(config: BaseRangeConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    // `base` is shared with the caller, only the table is allocated here.
+    pub fn configure(meta: &mut ConstraintSystem<F>, base: Column<Advice>) -> BaseRangeConfig {
+        // selectors used in lookups can not be simple selectors
+        let selector = meta.complex_selector();
+        let table = meta.lookup_table_column();
+
+        meta.enable_equality(base);
+
+        // on disabled rows the looked up value is 0, which both tables contain
+        meta.lookup("base range", |meta| {
+            let s = meta.query_selector(selector);
+            let base = meta.query_advice(base, Rotation::cur());
+            vec![(s * base, table)]
+        });
+
+        BaseRangeConfig {
+            base,
+            selector,
+            table,
+        }
+    }
+
+    pub fn load_table(
+        &self,
+        mut layouter: impl Layouter<F>,
+        domain: BaseDomain,
+    ) -> Result<(), Error> {
+        layouter.assign_table(
+            || "base table",
+            |mut table| {
+                for i in 0..=domain.max() {
+                    table.assign_cell(
+                        || "base",
+                        self.config.table,
+                        i as usize,
+                        || Value::known(F::from(i)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    pub fn check_base(
+        &self,
+        mut layouter: impl Layouter<F>,
+        base: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "base range",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                base.copy_advice(|| "base", &mut region, self.config.base, 0)?;
+                Ok(())
+            },
+        )
+    }
+}
+
+// Same statement as example2::TestCircuit, with the base restricted to `domain`.
+#[derive(Clone)]
+pub struct BaseRangedPowerCircuit<F> {
+    pub exp: usize,
+    pub domain: BaseDomain,
+    _marker: PhantomData<F>,
+}
+
+impl<F> BaseRangedPowerCircuit<F> {
+    pub fn new(exp: usize, domain: BaseDomain) -> Self {
+        Self {
+            exp,
+            domain,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for BaseRangedPowerCircuit<F> {
+    type Config = (PowerByNumConfig, BaseRangeConfig);
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.exp, self.domain)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let config = PowerByNumChip::configure(meta);
+        let range_config = BaseRangeChip::configure(meta, config.col_a);
+        (config, range_config)
+    }
+
+    fn synthesize(
+        &self,
+        (config, range_config): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PowerByNumChip::construct(config);
+        let range_chip = BaseRangeChip::construct(range_config);
+
+        range_chip.load_table(layouter.namespace(|| "base table"), self.domain)?;
+
+        // the base is range checked even for exp = 0
+        let (_, prev_b, mut prev_c) = chip.intial_assign(layouter.namespace(|| "first region"))?;
+        range_chip.check_base(layouter.namespace(|| "base range"), &prev_b)?;
+
+        // as in TestCircuit, exp = 0 exposes the constant 1 instead of the x^1 of the first row
+        if self.exp == 0 {
+            prev_c = chip.assign_one(layouter.namespace(|| "exp zero"))?;
+        }
+
+        for i in 1..self.exp {
+            prev_c = chip.subsequent_assign(
+                layouter.namespace(|| "subsequent region"),
+                i,
+                ChainMode::MultiplyByBase,
+                &prev_b,
+                &prev_c,
+            )?;
+        }
+
+        chip.expose_public(layouter.namespace(|| "out"), &prev_c, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BaseDomain, BaseRangedPowerCircuit};
+    use crate::example2::compute_output;
+    use halo2::{dev::MockProver, halo2curves::bn256::Fr};
+
+    fn verifies(k: u32, domain: BaseDomain, base: u64) -> bool {
+        verifies_exp(k, domain, base, 3)
+    }
+
+    fn verifies_exp(k: u32, domain: BaseDomain, base: u64, exp: usize) -> bool {
+        let circuit = BaseRangedPowerCircuit::new(exp, domain);
+        let input = Fr::from(base);
+        let public_input = vec![input, compute_output(input, exp)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.verify().is_ok()
+    }
+
+    #[test]
+    fn digit_base_test() {
+        assert!(verifies(5, BaseDomain::Digit, 0));
+        assert!(verifies(5, BaseDomain::Digit, 9));
+        assert!(!verifies(5, BaseDomain::Digit, 10));
+    }
+
+    #[test]
+    fn byte_base_test() {
+        // the 256 rows of the table need k = 9
+        assert!(verifies(9, BaseDomain::Byte, 200));
+        assert!(verifies(9, BaseDomain::Byte, 255));
+        assert!(!verifies(9, BaseDomain::Byte, 256));
+    }
+
+    #[test]
+    fn exp_zero_test() {
+        // x^0 = 1, with x still in the domain
+        assert!(verifies_exp(5, BaseDomain::Digit, 7, 0));
+        assert!(!verifies_exp(5, BaseDomain::Digit, 10, 0));
+
+        let circuit = BaseRangedPowerCircuit::new(0, BaseDomain::Digit);
+        let public_input = vec![Fr::from(7), Fr::from(7)];
+        let prover = MockProver::run(5, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,328 @@
+// Power chain with every intermediate power bounded by a public B.
+// It proves the relation R = { ( x, y, B; n): x^n = y, x^i <= B for 1 <= i <= n }.
+// Field arithmetic wraps silently at the modulus, so nothing in example2 stops x^i from
+// exceeding B. Here B, every x^i and every B - x^i are decomposed into BOUND_BITS bits with a
+// running sum acc_j = bit_j + 2 * acc_{j+1}, whose last accumulator is pinned to 0. With x^i and
+// B below 2^BOUND_BITS as integers, x^i > B makes the difference wrap to a value near the
+// modulus, which does not fit and fails the proof. The range check on x^i itself matters: a
+// base like -1 is far above B as an integer, yet B - (-1) = B + 1 fits.
+use crate::example2::{ChainMode, PowerByNumChip, PowerByNumConfig};
+use alloc::vec;
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+// B and the powers must stay below 2^BOUND_BITS, which the circuit checks.
+pub const BOUND_BITS: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct BoundConfig {
+    pub power: Column<Advice>,
+    pub bound: Column<Advice>,
+    pub acc: Column<Advice>,
+    pub bit: Column<Advice>,
+    pub diff_selector: Selector,
+    pub bit_selector: Selector,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BoundChip<F: PrimeField> {
+    config: BoundConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> BoundChip<F> {
+    pub fn construct(config: BoundConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    // `power`, `bound` and `acc` can be shared with other gates, e.g. the columns of the chain.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        power: Column<Advice>,
+        bound: Column<Advice>,
+        acc: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> BoundConfig {
+        let bit = meta.advice_column();
+        let diff_selector = meta.selector();
+        let bit_selector = meta.selector();
+
+        meta.enable_equality(bound);
+        meta.enable_equality(acc);
+
+        meta.create_gate("bound diff", |meta| {
+            let s = meta.query_selector(diff_selector);
+            let power = meta.query_advice(power, Rotation::cur());
+            let bound = meta.query_advice(bound, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![s * (bound - power - acc)]
+        });
+
+        meta.create_gate("bound bits", |meta| {
+            let s = meta.query_selector(bit_selector);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_next = meta.query_advice(acc, Rotation::next());
+            let one = Expression::Constant(F::ONE);
+            let two = Expression::Constant(F::from(2));
+            vec![
+                s.clone() * bit.clone() * (one - bit.clone()),
+                s * (acc - bit - two * acc_next),
+            ]
+        });
+
+        BoundConfig {
+            power,
+            bound,
+            acc,
+            bit,
+            diff_selector,
+            bit_selector,
+            instance,
+        }
+    }
+
+    // Decomposes the accumulator cell at row 0 of `region` into BOUND_BITS bits, failing unless
+    // it is below 2^BOUND_BITS.
+    fn decompose(
+        &self,
+        region: &mut Region<'_, F>,
+        mut acc_cell: AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        // halving after taking off the low bit keeps every row consistent, so an out of range
+        // value only shows up in the last accumulator
+        let mut acc = acc_cell.value().copied();
+        for row in 0..BOUND_BITS {
+            self.config.bit_selector.enable(region, row)?;
+
+            let bit = acc.map(|acc| F::from(acc.to_repr().as_ref()[0] as u64 & 1));
+            region.assign_advice(|| "bit", self.config.bit, row, || bit)?;
+
+            acc = (acc - bit) * Value::known(F::TWO_INV);
+            acc_cell = region.assign_advice(|| "acc", self.config.acc, row + 1, || acc)?;
+        }
+
+        region.constrain_constant(acc_cell.cell(), F::ZERO)
+    }
+
+    // Constrains value < 2^BOUND_BITS.
+    pub fn check_range(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "range check",
+            |mut region| {
+                let acc = value.copy_advice(|| "acc", &mut region, self.config.acc, 0)?;
+                self.decompose(&mut region, acc)
+            },
+        )
+    }
+
+    // Reads the bound at instance row `bound_row` and constrains it below 2^BOUND_BITS.
+    pub fn load_bound(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bound_row: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let bound = layouter.assign_region(
+            || "bound",
+            |mut region| {
+                region.assign_advice_from_instance(
+                    || "bound",
+                    self.config.instance,
+                    bound_row,
+                    self.config.bound,
+                    0,
+                )
+            },
+        )?;
+        self.check_range(layouter.namespace(|| "bound range"), &bound)?;
+        Ok(bound)
+    }
+
+    // Constrains power <= bound, both below 2^BOUND_BITS, `bound` as loaded by `load_bound`.
+    pub fn check_bound(
+        &self,
+        mut layouter: impl Layouter<F>,
+        power: &AssignedCell<F, F>,
+        bound: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        self.check_range(layouter.namespace(|| "power range"), power)?;
+
+        layouter.assign_region(
+            || "bound check",
+            |mut region| {
+                self.config.diff_selector.enable(&mut region, 0)?;
+
+                let power = power.copy_advice(|| "power", &mut region, self.config.power, 0)?;
+                let bound = bound.copy_advice(|| "bound", &mut region, self.config.bound, 0)?;
+
+                let diff = bound.value().copied() - power.value();
+                let acc = region.assign_advice(|| "acc", self.config.acc, 0, || diff)?;
+                self.decompose(&mut region, acc)
+            },
+        )
+    }
+}
+
+// Same statement as example2::TestCircuit, with x^1, ..., x^exp bounded by B.
+// Public instance: [x, y, B].
+#[derive(Default, Clone)]
+pub struct BoundedPowerCircuit<F> {
+    pub exp: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> BoundedPowerCircuit<F> {
+    pub fn new(exp: usize) -> Self {
+        Self {
+            exp,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for BoundedPowerCircuit<F> {
+    type Config = (PowerByNumConfig, BoundConfig);
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.exp)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let config = PowerByNumChip::configure(meta);
+        let bound_config = BoundChip::configure(
+            meta,
+            config.col_a,
+            config.col_b,
+            config.col_c,
+            config.instance,
+        );
+        (config, bound_config)
+    }
+
+    fn synthesize(
+        &self,
+        (config, bound_config): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PowerByNumChip::construct(config);
+        let bound_chip = BoundChip::construct(bound_config);
+
+        let bound = bound_chip.load_bound(layouter.namespace(|| "B"), 2)?;
+
+        // as in TestCircuit, exp = 0 lays out no multiplication row and exposes the constant 1,
+        // with no power to bound
+        let output = if self.exp == 0 {
+            chip.assign_one(layouter.namespace(|| "exp zero"))?
+        } else {
+            let (_, prev_b, mut prev_c) =
+                chip.intial_assign(layouter.namespace(|| "first region"))?;
+            bound_chip.check_bound(layouter.namespace(|| "x^1 <= B"), &prev_c, &bound)?;
+
+            for i in 1..self.exp {
+                prev_c = chip.subsequent_assign(
+                    layouter.namespace(|| "subsequent region"),
+                    i,
+                    ChainMode::MultiplyByBase,
+                    &prev_b,
+                    &prev_c,
+                )?;
+                bound_chip.check_bound(layouter.namespace(|| "x^i <= B"), &prev_c, &bound)?;
+            }
+            prev_c
+        };
+
+        chip.expose_public(layouter.namespace(|| "out"), &output, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundedPowerCircuit, BOUND_BITS};
+    use crate::example2::compute_output;
+    use halo2::{
+        dev::MockProver,
+        halo2curves::{bn256::Fr, ff::Field},
+    };
+
+    #[test]
+    fn bounded_power_test() {
+        let k = 9;
+        let input = Fr::from(3);
+        let bound = Fr::from(1000);
+
+        // 3, 9, ..., 243 all stay below 1000
+        let exp = 5;
+        let circuit = BoundedPowerCircuit::new(exp);
+        let public_input = vec![input, compute_output(input, exp), bound];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // 3^7 = 2187 is a valid power, but exceeds the bound
+        let exp = 7;
+        let circuit = BoundedPowerCircuit::new(exp);
+        let public_input = vec![input, compute_output(input, exp), bound];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn wrapped_power_test() {
+        let k = 9;
+        let bound = Fr::from(1000);
+
+        // -1 is p - 1 as an integer: B - (-1) = 1001 fits, but x^1 itself does not, even though
+        // (-1)^2 = 1 is small again
+        let input = -Fr::ONE;
+        let exp = 2;
+        let circuit = BoundedPowerCircuit::new(exp);
+        let public_input = vec![input, compute_output(input, exp), bound];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn bounded_exp_zero_test() {
+        let k = 9;
+        let input = Fr::from(3);
+        let bound = Fr::from(1000);
+        let circuit = BoundedPowerCircuit::new(0);
+
+        let prover = MockProver::run(k, &circuit, vec![vec![input, Fr::from(1), bound]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit, vec![vec![input, input, bound]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn bound_out_of_range_test() {
+        let k = 9;
+        let input = Fr::from(3);
+        let exp = 2;
+        let circuit = BoundedPowerCircuit::new(exp);
+
+        // B = 2^BOUND_BITS - 1 is the largest bound
+        let bound = Fr::from((1u64 << BOUND_BITS) - 1);
+        let public_input = vec![input, compute_output(input, exp), bound];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        let bound = Fr::from(1u64 << BOUND_BITS);
+        let public_input = vec![input, compute_output(input, exp), bound];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,160 @@
+// Power chain for a base fixed in the circuit.
+// It proves the relation R = { ( y; exp ): c^exp = y } for a constant c. Since c is known at
+// keygen, c, c^2 and c^4 are precomputed into a fixed column, and every row of the gate
+// s * (acc_next - acc * factor) multiplies the accumulator by one of them, folding up to four
+// multiplications of the naive chain into one row: exp = 4q + 2r + s takes q + r + s rows.
+use alloc::{format, vec, vec::Vec};
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+pub struct ConstantBaseConfig {
+    pub acc: Column<Advice>,
+    pub factor: Column<Fixed>,
+    pub selector: Selector,
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
+}
+
+// Exponents of the base multiplied in row by row, largest first, summing to `exp`.
+pub fn factor_exps(exp: usize) -> Vec<usize> {
+    let mut exps = vec![4; exp / 4];
+    if exp % 4 >= 2 {
+        exps.push(2);
+    }
+    if exp % 2 == 1 {
+        exps.push(1);
+    }
+    exps
+}
+
+// Public instance: [y]. Both the base and exp are structural.
+#[derive(Default, Clone)]
+pub struct ConstantBaseCircuit<F> {
+    pub base: F,
+    pub exp: usize,
+}
+
+impl<F> ConstantBaseCircuit<F> {
+    pub fn new(base: F, exp: usize) -> Self {
+        Self { base, exp }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for ConstantBaseCircuit<F> {
+    type Config = ConstantBaseConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let acc = meta.advice_column();
+        let factor = meta.fixed_column();
+        let selector = meta.selector();
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        meta.enable_equality(acc);
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate("mul by constant", |meta| {
+            let s = meta.query_selector(selector);
+            let acc_cur = meta.query_advice(acc, Rotation::cur());
+            let acc_next = meta.query_advice(acc, Rotation::next());
+            let factor = meta.query_fixed(factor, Rotation::cur());
+            vec![s * (acc_next - acc_cur * factor)]
+        });
+
+        ConstantBaseConfig {
+            acc,
+            factor,
+            selector,
+            instance,
+            constant,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let result = layouter.assign_region(
+            || "constant base chain",
+            |mut region| {
+                let mut acc =
+                    region.assign_advice_from_constant(|| "acc", config.acc, 0, F::ONE)?;
+
+                for (row, exp) in factor_exps(self.exp).into_iter().enumerate() {
+                    config.selector.enable(&mut region, row)?;
+
+                    let factor = self.base.pow([exp as u64]);
+                    region.assign_fixed(
+                        || format!("c^{exp}"),
+                        config.factor,
+                        row,
+                        || Value::known(factor),
+                    )?;
+
+                    let next = acc.value().map(|acc| *acc * factor);
+                    acc = region.assign_advice(|| "acc", config.acc, row + 1, || next)?;
+                }
+
+                Ok(acc)
+            },
+        )?;
+
+        layouter.constrain_instance(result.cell(), config.instance, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{factor_exps, ConstantBaseCircuit};
+    use crate::dry_run::dry_run;
+    use crate::example2::{compute_output, min_k, TestCircuit};
+    use halo2::{dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn factor_exps_test() {
+        assert_eq!(factor_exps(0), Vec::<usize>::new());
+        assert_eq!(factor_exps(7), vec![4, 2, 1]);
+        assert_eq!(factor_exps(9), vec![4, 4, 1]);
+    }
+
+    #[test]
+    fn constant_base_test() {
+        let base = Fr::from(3);
+
+        // both chains agree on 3^exp
+        for exp in [0, 1, 2, 3, 5, 8, 13] {
+            let output = compute_output(base, exp);
+
+            let naive = TestCircuit::new(exp);
+            let prover = MockProver::run(min_k(exp), &naive, vec![vec![base, output]]).unwrap();
+            prover.assert_satisfied();
+
+            let circuit = ConstantBaseCircuit::new(base, exp);
+            let prover = MockProver::run(4, &circuit, vec![vec![output]]).unwrap();
+            prover.assert_satisfied();
+
+            // one row per factor, and the one holding the result
+            let rows = dry_run(&circuit).unwrap().rows();
+            assert_eq!(rows, factor_exps(exp).len() + 1);
+        }
+
+        // 13 multiplications fold into 4 + 4 + 4 + 1
+        let naive_rows = dry_run(&TestCircuit::<Fr>::new(13)).unwrap().rows();
+        let folded_rows = dry_run(&ConstantBaseCircuit::new(base, 13)).unwrap().rows();
+        assert_eq!((naive_rows, folded_rows), (13, 5));
+
+        // 3^5 is not 3^4
+        let circuit = ConstantBaseCircuit::new(base, 5);
+        let prover = MockProver::run(4, &circuit, vec![vec![compute_output(base, 4)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,50 @@
+// Fingerprint of a verifying key.
+// On-chain verifiers usually commit to the vk they were generated for, so a prover and a
+// verifier can compare 32 bytes instead of whole keys. The digest is Blake2b over the
+// `SerdeFormat::RawBytes` encoding used by persistence, which covers the fixed and permutation
+// commitments as well as the domain, so it changes with the circuit, its k and the params.
+use halo2::{halo2curves::bn256::G1Affine, plonk::VerifyingKey, SerdeFormat};
+
+pub fn vk_digest(vk: &VerifyingKey<G1Affine>) -> [u8; 32] {
+    let bytes = vk.to_bytes(SerdeFormat::RawBytes);
+    let hash = blake2b_simd::Params::new().hash_length(32).hash(&bytes);
+
+    let mut digest = [0; 32];
+    digest.copy_from_slice(hash.as_bytes());
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::vk_digest;
+    use crate::example2::{min_k, TestCircuit};
+    use halo2::{
+        halo2curves::bn256::{Bn256, Fr},
+        plonk::{keygen_vk, VerifyingKey},
+        poly::kzg::commitment::ParamsKZG,
+        SerdeFormat,
+    };
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn vk_digest_test() {
+        let exp = 3;
+        let k = min_k(exp);
+        let circuit = TestCircuit::<Fr>::new(exp);
+
+        let params = ParamsKZG::<Bn256>::setup(k, OsRng);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let digest = vk_digest(&vk);
+
+        // the same key read back from its bytes
+        let bytes = vk.to_bytes(SerdeFormat::RawBytes);
+        let reread = VerifyingKey::from_bytes::<TestCircuit<Fr>>(&bytes, SerdeFormat::RawBytes)
+            .expect("vk deserialization failed");
+        assert_eq!(vk_digest(&reread), digest);
+
+        // the same circuit over a larger domain
+        let params = ParamsKZG::<Bn256>::setup(k + 1, OsRng);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        assert_ne!(vk_digest(&vk), digest);
+    }
+}
@@ -0,0 +1,204 @@
+// Power or zero base.
+// It proves the relation R = { ( x, y; exp): x^exp = y or x = 0 }. The chain of example2
+// computes p = x^exp as usual, then one "select" row holds x, p, y and a boolean branch b:
+//
+// | a | b | c | branch |
+// | x | p | y | b      |
+//
+// b = 0 asks for p = y and b = 1 for x = 0, so the gate is
+// b * (1 - b) = 0, b * x = 0 and (1 - b) * (p - y) = 0, all under the select selector.
+use crate::example2::{ChainMode, PowerByNumChip, PowerByNumConfig};
+use alloc::vec;
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+pub struct DisjunctionConfig {
+    pub power: PowerByNumConfig,
+    pub branch: Column<Advice>,
+    pub selector: Selector,
+}
+
+#[derive(Debug, Clone)]
+pub struct DisjunctionChip<F: PrimeField> {
+    config: DisjunctionConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> DisjunctionChip<F> {
+    pub fn construct(config: DisjunctionConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> DisjunctionConfig {
+        let power = PowerByNumChip::configure(meta);
+        let branch = meta.advice_column();
+        let selector = meta.selector();
+
+        meta.create_gate("select", |meta| {
+            let s = meta.query_selector(selector);
+            let x = meta.query_advice(power.col_a, Rotation::cur());
+            let p = meta.query_advice(power.col_b, Rotation::cur());
+            let y = meta.query_advice(power.col_c, Rotation::cur());
+            let b = meta.query_advice(branch, Rotation::cur());
+            let one = Expression::Constant(F::ONE);
+
+            vec![
+                s.clone() * b.clone() * (one.clone() - b.clone()),
+                s.clone() * b.clone() * x,
+                s * (one - b) * (p - y),
+            ]
+        });
+
+        DisjunctionConfig {
+            power,
+            branch,
+            selector,
+        }
+    }
+
+    pub fn power_chip(&self) -> PowerByNumChip<F> {
+        PowerByNumChip::construct(self.config.power.clone())
+    }
+
+    // Lays out the select row, with y copied from instance row `y_row`.
+    pub fn assign_select(
+        &self,
+        mut layouter: impl Layouter<F>,
+        base: &AssignedCell<F, F>,
+        power: &AssignedCell<F, F>,
+        y_row: usize,
+        branch: Value<bool>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "select",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                base.copy_advice(|| "x", &mut region, self.config.power.col_a, 0)?;
+                power.copy_advice(|| "x^exp", &mut region, self.config.power.col_b, 0)?;
+                region.assign_advice_from_instance(
+                    || "y",
+                    self.config.power.instance,
+                    y_row,
+                    self.config.power.col_c,
+                    0,
+                )?;
+                region.assign_advice(
+                    || "branch",
+                    self.config.branch,
+                    0,
+                    || branch.map(|b| if b { F::ONE } else { F::ZERO }),
+                )?;
+
+                Ok(())
+            },
+        )
+    }
+}
+
+// Public instance: [x, y]. `exp` is structural and must be at least 1, `branch` is the
+// prover's choice: false for x^exp = y, true for x = 0.
+#[derive(Default, Clone)]
+pub struct DisjunctionCircuit<F> {
+    pub exp: usize,
+    pub branch: Value<bool>,
+    _marker: PhantomData<F>,
+}
+
+impl<F> DisjunctionCircuit<F> {
+    pub fn new(exp: usize, branch: Value<bool>) -> Self {
+        Self {
+            exp,
+            branch,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for DisjunctionCircuit<F> {
+    type Config = DisjunctionConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.exp, Value::unknown())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        DisjunctionChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = DisjunctionChip::construct(config);
+        let power_chip = chip.power_chip();
+
+        let (_, base, mut power) = power_chip.intial_assign(layouter.namespace(|| "x"))?;
+        for i in 1..self.exp {
+            power = power_chip.subsequent_assign(
+                layouter.namespace(|| "subsequent region"),
+                i,
+                ChainMode::MultiplyByBase,
+                &base,
+                &power,
+            )?;
+        }
+
+        chip.assign_select(
+            layouter.namespace(|| "select"),
+            &base,
+            &power,
+            1,
+            self.branch,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DisjunctionCircuit;
+    use halo2::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn disjunction_test() {
+        let k = 4;
+
+        // 2^3 = 8
+        let circuit = DisjunctionCircuit::new(3, Value::known(false));
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(2), Fr::from(8)]]).unwrap();
+        prover.assert_satisfied();
+
+        // x = 0 lets any y through
+        let circuit = DisjunctionCircuit::new(3, Value::known(true));
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(0), Fr::from(5)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn disjunction_rejection_test() {
+        let k = 4;
+
+        // 2^3 is not 9 and 2 is not 0, whichever branch the prover takes
+        for branch in [false, true] {
+            let circuit = DisjunctionCircuit::new(3, Value::known(branch));
+            let public_input = vec![Fr::from(2), Fr::from(9)];
+            let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+
+        // a zero base still needs its branch: 0^3 is not 5
+        let circuit = DisjunctionCircuit::new(3, Value::known(false));
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(0), Fr::from(5)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
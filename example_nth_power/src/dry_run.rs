@@ -0,0 +1,381 @@
+// Witness-free dry run of a circuit.
+// The circuit is synthesized through its floor planner into an `Assignment` that only records
+// where things land, which tells how many rows a configuration occupies before proving it.
+// Enabled selectors are recorded too, so tests can check every gate was switched on where
+// it should be, and so are fixed values, which unlike witnesses are known without them: they
+// show the constants a circuit commits to, e.g. for an audit.
+// `trace` synthesizes the circuit with its witness and instances instead, and records the
+// advice values as well, to follow an assignment row by row.
+use alloc::{string::String, vec, vec::Vec};
+use core::{fmt, marker::PhantomData};
+use halo2::{
+    circuit::Value,
+    dev::MockProver,
+    halo2curves::ff::{Field, FromUniformBytes},
+    plonk::*,
+};
+
+#[derive(Debug, Default)]
+pub struct DryRun<F> {
+    rows: usize,
+    instance_rows: Vec<usize>,
+    // one entry per enabled (selector, row)
+    enabled_selectors: Vec<Selector>,
+    fixed: Vec<(Column<Fixed>, usize, F)>,
+    // only set by `trace`: a dry run has no instances and only unknown advice values
+    instances: Vec<Vec<F>>,
+    advice: Vec<(Column<Advice>, usize, F)>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Copy> DryRun<F> {
+    // The (row, value) pairs assigned to `column`, by row. Constants used through
+    // `assign_advice_from_constant` land in the column passed to `enable_constant`.
+    pub fn fixed_values(&self, column: Column<Fixed>) -> Vec<(usize, F)> {
+        let mut values: Vec<_> = self
+            .fixed
+            .iter()
+            .filter(|(assigned, _, _)| *assigned == column)
+            .map(|&(_, row, value)| (row, value))
+            .collect();
+        values.sort_by_key(|&(row, _)| row);
+        values
+    }
+
+    // Same as `fixed_values` for the advice values recorded by `trace`.
+    pub fn advice_values(&self, column: Column<Advice>) -> Vec<(usize, F)> {
+        let mut values: Vec<_> = self
+            .advice
+            .iter()
+            .filter(|(assigned, _, _)| *assigned == column)
+            .map(|&(_, row, value)| (row, value))
+            .collect();
+        values.sort_by_key(|&(row, _)| row);
+        values
+    }
+}
+
+impl<F> DryRun<F> {
+    // Number of rows used, i.e. the highest assigned row plus one.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    // Length each instance column needs, i.e. the highest row copied to or from plus one.
+    pub fn instance_rows(&self) -> &[usize] {
+        &self.instance_rows
+    }
+
+    // Number of rows `selector` was enabled on.
+    pub fn enabled_rows(&self, selector: &Selector) -> usize {
+        self.enabled_selectors
+            .iter()
+            .filter(|&enabled| enabled == selector)
+            .count()
+    }
+
+    fn touch(&mut self, row: usize) {
+        self.rows = self.rows.max(row + 1);
+    }
+
+    fn touch_instance(&mut self, column: Column<Any>, row: usize) {
+        if *column.column_type() == Any::Instance {
+            if self.instance_rows.len() <= column.index() {
+                self.instance_rows.resize(column.index() + 1, 0);
+            }
+            let rows = &mut self.instance_rows[column.index()];
+            *rows = (*rows).max(row + 1);
+        }
+    }
+}
+
+// Synthesizes `circuit.without_witnesses()` and returns what was recorded.
+pub fn dry_run<F: Field, C: Circuit<F>>(circuit: &C) -> Result<DryRun<F>, Error> {
+    record(&circuit.without_witnesses(), vec![])
+}
+
+// Synthesizes `circuit` itself against `instances`, recording the advice values too.
+pub fn trace<F: Field, C: Circuit<F>>(
+    circuit: &C,
+    instances: Vec<Vec<F>>,
+) -> Result<DryRun<F>, Error> {
+    record(circuit, instances)
+}
+
+fn record<F: Field, C: Circuit<F>>(
+    circuit: &C,
+    instances: Vec<Vec<F>>,
+) -> Result<DryRun<F>, Error> {
+    let mut meta = ConstraintSystem::default();
+    #[cfg(feature = "circuit-params")]
+    let config = C::configure_with_params(&mut meta, circuit.params());
+    #[cfg(not(feature = "circuit-params"))]
+    let config = C::configure(&mut meta);
+
+    let mut dry_run = DryRun {
+        instances,
+        ..DryRun::default()
+    };
+    C::FloorPlanner::synthesize(&mut dry_run, circuit, config, meta.constants().clone())?;
+
+    Ok(dry_run)
+}
+
+#[derive(Debug)]
+pub enum RunError {
+    // the instance column at `column` holds fewer rows than the circuit copies from or to
+    InstanceTooShort {
+        column: usize,
+        expected: usize,
+        actual: usize,
+    },
+    // the rows do not fit a 2^k domain, the smallest one they fit is 2^min_k
+    KTooSmall {
+        k: u32,
+        min_k: u32,
+    },
+    Synthesis(Error),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::InstanceTooShort {
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "instance column {column} has {actual} values but the circuit uses {expected}"
+            ),
+            RunError::KTooSmall { k, min_k } => write!(
+                f,
+                "k = {k} leaves too few rows for the circuit, use k = {min_k} or more"
+            ),
+            RunError::Synthesis(source) => write!(f, "{source}"),
+        }
+    }
+}
+
+// `MockProver::run` that first checks the circuit fits in 2^k rows and the instance columns
+// are long enough for the rows the circuit exposes, instead of panicking or failing later on
+// an unrelated looking cell. For the power circuits the suggested k is `min_k(exp)`.
+pub fn run_checked<F: Field + FromUniformBytes<64> + Ord, C: Circuit<F>>(
+    k: u32,
+    circuit: &C,
+    instances: Vec<Vec<F>>,
+) -> Result<MockProver<F>, RunError> {
+    let dry_run = dry_run(circuit).map_err(RunError::Synthesis)?;
+
+    // MockProver asserts the domain holds the blinding rows, and errors on assigned rows
+    // beyond the usable ones
+    let mut meta = ConstraintSystem::<F>::default();
+    #[cfg(feature = "circuit-params")]
+    C::configure_with_params(&mut meta, circuit.params());
+    #[cfg(not(feature = "circuit-params"))]
+    C::configure(&mut meta);
+    let reserved = meta.blinding_factors() + 1;
+    let fits = |k: u32| {
+        let n = 1usize << k;
+        n >= meta.minimum_rows() && n - reserved >= dry_run.rows()
+    };
+    if !fits(k) {
+        let min_k = (1..).find(|&k| fits(k)).unwrap();
+        return Err(RunError::KTooSmall { k, min_k });
+    }
+
+    for (column, &expected) in dry_run.instance_rows().iter().enumerate() {
+        let actual = instances.get(column).map_or(0, Vec::len);
+        if actual < expected {
+            return Err(RunError::InstanceTooShort {
+                column,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    MockProver::run(k, circuit, instances).map_err(RunError::Synthesis)
+}
+
+impl<F: Field> Assignment<F> for DryRun<F> {
+    fn enter_region<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn annotate_column<A, AR>(&mut self, _: A, _: Column<Any>)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+    }
+
+    fn exit_region(&mut self) {}
+
+    fn enable_selector<A, AR>(&mut self, _: A, selector: &Selector, row: usize) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.touch(row);
+        self.enabled_selectors.push(*selector);
+        Ok(())
+    }
+
+    fn query_instance(&self, column: Column<Instance>, row: usize) -> Result<Value<F>, Error> {
+        let value = self
+            .instances
+            .get(column.index())
+            .and_then(|values| values.get(row));
+        Ok(value.map_or(Value::unknown(), |&value| Value::known(value)))
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        column: Column<Advice>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.touch(row);
+        // unknown in a dry run, so only recorded by a trace
+        to().map(|value| {
+            let value: Assigned<F> = value.into();
+            self.advice.push((column, row, value.evaluate()));
+        });
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        column: Column<Fixed>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.touch(row);
+        // fixed values never depend on the witness
+        to().map(|value| {
+            let value: Assigned<F> = value.into();
+            self.fixed.push((column, row, value.evaluate()));
+        });
+        Ok(())
+    }
+
+    fn copy(
+        &mut self,
+        left_column: Column<Any>,
+        left_row: usize,
+        right_column: Column<Any>,
+        right_row: usize,
+    ) -> Result<(), Error> {
+        self.touch_instance(left_column, left_row);
+        self.touch_instance(right_column, right_row);
+        Ok(())
+    }
+
+    // only used to pad lookup tables up to the end of the domain, which is not "used" space
+    fn fill_from_row(
+        &mut self,
+        _: Column<Fixed>,
+        _: usize,
+        _: Value<Assigned<F>>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn get_challenge(&self, _: Challenge) -> Value<F> {
+        Value::unknown()
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dry_run, run_checked, RunError};
+    use crate::example2::{estimate_rows, min_k, TestCircuit};
+    use halo2::{
+        halo2curves::{bn256::Fr, ff::Field},
+        plonk::{Circuit, ConstraintSystem},
+    };
+
+    #[test]
+    fn dry_run_test() {
+        // the estimate matches what the floor planner actually lays out
+        for exp in [0, 1, 2, 5, 13] {
+            let rows = dry_run(&TestCircuit::<Fr>::new(exp)).unwrap().rows();
+            assert_eq!(rows, estimate_rows(exp));
+        }
+    }
+
+    #[test]
+    fn fixed_values_test() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let config = TestCircuit::<Fr>::configure(&mut meta);
+
+        // the only constant of the chain is the 1 that x is multiplied with first
+        let dry_run = dry_run(&TestCircuit::<Fr>::new(3)).unwrap();
+        assert_eq!(dry_run.fixed_values(config.constant), vec![(0, Fr::ONE)]);
+    }
+
+    #[test]
+    fn run_checked_test() {
+        let circuit = TestCircuit::<Fr>::new(3);
+
+        // the output at row 1 was forgotten
+        let result = run_checked(min_k(3), &circuit, vec![vec![Fr::from(2)]]);
+        assert!(matches!(
+            result,
+            Err(RunError::InstanceTooShort {
+                column: 0,
+                expected: 2,
+                actual: 1,
+            })
+        ));
+
+        let prover = run_checked(min_k(3), &circuit, vec![vec![Fr::from(2), Fr::from(8)]]);
+        prover.unwrap().assert_satisfied();
+    }
+
+    #[test]
+    fn run_checked_k_too_small_test() {
+        let exp = 6;
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let public_input = vec![vec![Fr::from(2), Fr::from(64)]];
+
+        let error = run_checked(1, &circuit, public_input.clone()).unwrap_err();
+        assert!(
+            matches!(error, RunError::KTooSmall { k: 1, min_k: suggested } if suggested == min_k(exp))
+        );
+        assert_eq!(
+            error.to_string(),
+            "k = 1 leaves too few rows for the circuit, use k = 4 or more"
+        );
+
+        let prover = run_checked(min_k(exp), &circuit, public_input).unwrap();
+        prover.assert_satisfied();
+    }
+}
@@ -0,0 +1,138 @@
+// Keygen, prove and verify in one call, for end-to-end checks of any example circuit.
+// Fresh params are drawn for each call, KZG over bn256 and IPA over pasta, so these are meant
+// for tests rather than for reusing keys. Keygen and proving failures panic, only the
+// verifier's answer is returned.
+use alloc::{vec, vec::Vec};
+use halo2::{
+    halo2curves::{
+        bn256::{Bn256, Fr},
+        pasta::{EqAffine, Fp},
+    },
+    plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, Circuit},
+    poly::{
+        commitment::ParamsProver,
+        ipa::{
+            commitment::{IPACommitmentScheme, ParamsIPA},
+            multiopen::{ProverIPA, VerifierIPA},
+            strategy::AccumulatorStrategy as IpaAccumulatorStrategy,
+        },
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::{ProverGWC, VerifierGWC},
+            strategy::AccumulatorStrategy,
+        },
+        VerificationStrategy,
+    },
+    transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
+    },
+};
+use rand::rngs::OsRng;
+
+// `instances` holds one slice per instance column of the circuit.
+pub fn prove_verify_kzg<C: Circuit<Fr>>(k: u32, circuit: C, instances: &[&[Fr]]) -> bool {
+    let params = ParamsKZG::<Bn256>::setup(k, OsRng);
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+
+    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+    create_proof::<KZGCommitmentScheme<_>, ProverGWC<_>, _, _, _, _>(
+        &params,
+        &pk,
+        &[circuit],
+        &[instances],
+        OsRng,
+        &mut transcript,
+    )
+    .expect("proof generation failed");
+    let proof: Vec<u8> = transcript.finalize();
+
+    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof.as_slice());
+    let strategy = match verify_proof::<KZGCommitmentScheme<_>, VerifierGWC<_>, _, _, _>(
+        params.verifier_params(),
+        pk.get_vk(),
+        AccumulatorStrategy::new(params.verifier_params()),
+        &[instances],
+        &mut transcript,
+    ) {
+        Ok(strategy) => strategy,
+        // a malformed proof is just a rejected one here
+        Err(_) => return false,
+    };
+
+    VerificationStrategy::<_, VerifierGWC<_>>::finalize(strategy)
+}
+
+pub fn prove_verify_ipa<C: Circuit<Fp>>(k: u32, circuit: C, instances: &[&[Fp]]) -> bool {
+    let params = ParamsIPA::<EqAffine>::new(k);
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+
+    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+    create_proof::<IPACommitmentScheme<_>, ProverIPA<_>, _, _, _, _>(
+        &params,
+        &pk,
+        &[circuit],
+        &[instances],
+        OsRng,
+        &mut transcript,
+    )
+    .expect("proof generation failed");
+    let proof: Vec<u8> = transcript.finalize();
+
+    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof.as_slice());
+    let strategy = match verify_proof::<IPACommitmentScheme<_>, VerifierIPA<_>, _, _, _>(
+        params.verifier_params(),
+        pk.get_vk(),
+        IpaAccumulatorStrategy::new(params.verifier_params()),
+        &[instances],
+        &mut transcript,
+    ) {
+        Ok(strategy) => strategy,
+        // a malformed proof is just a rejected one here
+        Err(_) => return false,
+    };
+
+    VerificationStrategy::<_, VerifierIPA<_>>::finalize(strategy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prove_verify_ipa, prove_verify_kzg};
+    use crate::example2::{min_k, TestCircuit};
+    use halo2::halo2curves::{bn256::Fr, pasta::Fp};
+
+    #[test]
+    fn prove_verify_kzg_test() {
+        let exp = 3;
+        let circuit = TestCircuit::<Fr>::new(exp);
+
+        // 2^3 = 8
+        let public_input = [Fr::from(2), Fr::from(8)];
+        assert!(prove_verify_kzg(
+            min_k(exp),
+            circuit.clone(),
+            &[&public_input]
+        ));
+
+        // the prover does not check the statement, the verifier rejects the proof
+        let public_input = [Fr::from(2), Fr::from(9)];
+        assert!(!prove_verify_kzg(min_k(exp), circuit, &[&public_input]));
+    }
+
+    #[test]
+    fn prove_verify_ipa_test() {
+        let exp = 3;
+        let circuit = TestCircuit::<Fp>::new(exp);
+
+        let public_input = [Fp::from(2), Fp::from(8)];
+        assert!(prove_verify_ipa(
+            min_k(exp),
+            circuit.clone(),
+            &[&public_input]
+        ));
+
+        let public_input = [Fp::from(2), Fp::from(9)];
+        assert!(!prove_verify_ipa(min_k(exp), circuit, &[&public_input]));
+    }
+}
@@ -0,0 +1,132 @@
+// Errors of the example2 power chip.
+// halo2 reports every synthesis failure as a bare `plonk::Error`; wrapping it with the stage
+// that failed tells which part of the chain to look at.
+use core::fmt;
+use halo2::plonk::Error;
+
+#[derive(Debug)]
+pub enum PowerError {
+    // the first row, 1 * x, or the constant 1 for exp = 0
+    InitialAssign(Error),
+    // the `step`-th multiplication after the first row
+    SubsequentAssign { step: usize, source: Error },
+    // constraining a result to the instance column
+    Expose(Error),
+    // anything raised outside of the chip
+    Halo2(Error),
+}
+
+impl fmt::Display for PowerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PowerError::InitialAssign(source) => {
+                write!(f, "failed to assign the initial row: {source}")
+            }
+            PowerError::SubsequentAssign { step, source } => {
+                write!(f, "failed to assign multiplication step {step}: {source}")
+            }
+            PowerError::Expose(source) => write!(f, "failed to expose a public cell: {source}"),
+            PowerError::Halo2(source) => write!(f, "{source}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PowerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PowerError::InitialAssign(source)
+            | PowerError::SubsequentAssign { source, .. }
+            | PowerError::Expose(source)
+            | PowerError::Halo2(source) => Some(source),
+        }
+    }
+}
+
+impl From<Error> for PowerError {
+    fn from(error: Error) -> Self {
+        PowerError::Halo2(error)
+    }
+}
+
+// `Circuit::synthesize` has to return a plonk error, so the stage is dropped on the way out.
+impl From<PowerError> for Error {
+    fn from(error: PowerError) -> Self {
+        match error {
+            PowerError::InitialAssign(source)
+            | PowerError::SubsequentAssign { source, .. }
+            | PowerError::Expose(source)
+            | PowerError::Halo2(source) => source,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PowerError;
+    use crate::example2::{min_k, PowerByNumChip, PowerByNumConfig};
+    use halo2::{
+        circuit::*,
+        halo2curves::bn256::{Bn256, Fr},
+        plonk::*,
+        poly::kzg::commitment::ParamsKZG,
+    };
+    use rand::rngs::OsRng;
+    use std::{cell::RefCell, rc::Rc};
+
+    // Exposes x^1 to `row`. synthesize can only hand a plonk error back, so the chip error is
+    // kept aside for the test to look at.
+    #[derive(Default, Clone)]
+    struct ExposeToRowCircuit {
+        row: usize,
+        error: Rc<RefCell<Option<PowerError>>>,
+    }
+
+    impl Circuit<Fr> for ExposeToRowCircuit {
+        type Config = PowerByNumConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        #[cfg(feature = "circuit-params")]
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            PowerByNumChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let chip = PowerByNumChip::construct(config);
+
+            let (_, _, init_c) = chip.intial_assign(layouter.namespace(|| "first region"))?;
+            if let Err(error) = chip.expose_public(layouter.namespace(|| "out"), &init_c, self.row)
+            {
+                *self.error.borrow_mut() = Some(error);
+                return Err(Error::Synthesis);
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn expose_error_test() {
+        let k = min_k(1);
+        let params = ParamsKZG::<Bn256>::setup(k, OsRng);
+
+        // keygen rejects copies to rows past the usable part of the domain
+        let circuit = ExposeToRowCircuit {
+            row: 1 << k,
+            ..Default::default()
+        };
+        assert!(keygen_vk(&params, &circuit).is_err());
+
+        let error = circuit.error.borrow_mut().take();
+        assert!(matches!(error, Some(PowerError::Expose(_))));
+    }
+}
@@ -0,0 +1,168 @@
+// Powers by an even exponent.
+// It proves the relation R = { ( x, y; exp ): x^exp = y, exp = 0 mod 2 } with the
+// square-and-multiply ladder, which already witnesses the bits of the private exponent. The
+// parity gate checks exp = 2 * (exp >> 1) on the ladder's last two recomposed prefixes, which
+// holds exactly when the least significant bit is zero.
+use crate::square_multiply::{SquareMultiplyChip, SquareMultiplyConfig};
+use alloc::vec;
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+pub struct ParityConfig {
+    pub half: Column<Advice>,
+    pub exp: Column<Advice>,
+    pub selector: Selector,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParityChip<F: PrimeField> {
+    config: ParityConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> ParityChip<F> {
+    pub fn construct(config: ParityConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    // The columns are shared with the ladder, and need equality enabled.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        half: Column<Advice>,
+        exp: Column<Advice>,
+    ) -> ParityConfig {
+        let selector = meta.selector();
+
+        meta.create_gate("even", |meta| {
+            let s = meta.query_selector(selector);
+            let half = meta.query_advice(half, Rotation::cur());
+            let exp = meta.query_advice(exp, Rotation::cur());
+            vec![s * (exp - half * F::from(2))]
+        });
+
+        ParityConfig {
+            half,
+            exp,
+            selector,
+        }
+    }
+
+    // Constrains exp = 2 * half, with half the exponent recomposed without its last bit.
+    pub fn check_even(
+        &self,
+        mut layouter: impl Layouter<F>,
+        half: &AssignedCell<F, F>,
+        exp: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "even",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                half.copy_advice(|| "exp >> 1", &mut region, self.config.half, 0)?;
+                exp.copy_advice(|| "exp", &mut region, self.config.exp, 0)?;
+                Ok(())
+            },
+        )
+    }
+}
+
+// Public instance: [x, y]. `num_bits` is structural, at least 1, and bounds exp below
+// 2^num_bits.
+#[derive(Default, Clone)]
+pub struct EvenPowerCircuit<F> {
+    pub exp: Value<u64>,
+    pub num_bits: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> EvenPowerCircuit<F> {
+    pub fn new(exp: Value<u64>, num_bits: usize) -> Self {
+        Self {
+            exp,
+            num_bits,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for EvenPowerCircuit<F> {
+    type Config = (SquareMultiplyConfig, ParityConfig);
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(Value::unknown(), self.num_bits)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let config = SquareMultiplyChip::configure(meta);
+        let parity_config = ParityChip::configure(meta, config.col_acc, config.col_exp);
+        (config, parity_config)
+    }
+
+    fn synthesize(
+        &self,
+        (config, parity_config): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = SquareMultiplyChip::construct(config);
+        let parity_chip = ParityChip::construct(parity_config);
+
+        let (result, prefixes) = chip.assign_with_prefixes(
+            layouter.namespace(|| "square and multiply"),
+            self.exp,
+            self.num_bits,
+        )?;
+        parity_chip.check_even(
+            layouter.namespace(|| "parity"),
+            &prefixes[self.num_bits - 1],
+            &prefixes[self.num_bits],
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "out"), &result, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EvenPowerCircuit;
+    use crate::example2::compute_output;
+    use halo2::{
+        circuit::Value,
+        dev::{MockProver, VerifyFailure},
+        halo2curves::bn256::Fr,
+    };
+
+    #[test]
+    fn even_power_test() {
+        let k = 4;
+        let num_bits = 4;
+        let base = Fr::from(3);
+
+        // 3^4 = 81
+        let circuit = EvenPowerCircuit::new(Value::known(4), num_bits);
+        let public_input = vec![base, compute_output(base, 4)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // 3^5 = 243 is computed correctly, only the parity gate fails
+        let circuit = EvenPowerCircuit::new(Value::known(5), num_bits);
+        let public_input = vec![base, compute_output(base, 5)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert_eq!(failures.len(), 1);
+        assert!(matches!(
+            &failures[0],
+            VerifyFailure::ConstraintNotSatisfied { constraint, .. }
+                if constraint.to_string().contains("even")
+        ));
+    }
+}
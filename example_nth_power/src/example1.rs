@@ -1,5 +1,6 @@
+use alloc::vec;
+use core::marker::PhantomData;
 use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
-use std::marker::PhantomData;
 
 // Generate halo2 zkp proof for n-th power of an integer.
 // More formally, it prove the relation R = { ( x, y; exp): x^exp = y } where public input x,y and private input exp.
@@ -61,6 +62,8 @@ impl<F: FieldExt> PowerByNumChip<F> {
         }
     }
 
+    // The multiplicative identity seeding col_a comes from the constant column, so the instance
+    // only holds [x, y], the same convention as example2.
     pub fn intial_assign(
         &self,
         mut layouter: impl Layouter<F>,
@@ -195,7 +198,14 @@ mod tests {
     use std::marker::PhantomData;
 
     use super::TestCircuit;
-    use halo2_proofs::{dev::MockProver, pasta::Fp};
+    use halo2_proofs::{
+        dev::MockProver,
+        pasta::{pallas, Fp, Fq},
+        plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, SingleVerifier},
+        poly::commitment::Params,
+        transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+    };
+    use rand::rngs::OsRng;
 
     #[test]
     fn example_test1() {
@@ -213,4 +223,54 @@ mod tests {
         println!("{:?}", prover);
         prover.assert_satisfied();
     }
+
+    #[test]
+    fn example_test1_constant_seed() {
+        let k = 6;
+        let circuit = TestCircuit(PhantomData);
+
+        // 1 is not read from the instance: a leading 1 shifts x and y to the wrong rows
+        let public_input = vec![Fp::from(1), Fp::from(2), Fp::from(4096)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+
+        let public_input = vec![Fp::from(2), Fp::from(4096)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // The other half of the Pasta cycle: pallas commits to vectors over Fq.
+    #[test]
+    fn example_test1_pallas() {
+        let params: Params<pallas::Affine> = Params::new(7);
+        let circuit = TestCircuit::<Fq>(PhantomData);
+
+        let vk = keygen_vk(&params, &circuit).expect("vk generation failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("pk generation failed");
+
+        let public_input = [Fq::from(2), Fq::from(4096)];
+
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof(
+            &params,
+            &pk,
+            &[circuit],
+            &[&[&public_input]],
+            OsRng,
+            &mut transcript,
+        )
+        .expect("proof generation failed");
+        let proof = transcript.finalize();
+
+        let strategy = SingleVerifier::new(&params);
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+        assert!(verify_proof(
+            &params,
+            pk.get_vk(),
+            strategy,
+            &[&[&public_input]],
+            &mut transcript
+        )
+        .is_ok());
+    }
 }
@@ -1,6 +1,15 @@
 // Same with example1 but uses different library which is from PSE team
-use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
-use std::marker::PhantomData;
+use crate::dry_run::trace;
+use crate::error::PowerError;
+use crate::mul::{enable_equality_once, MulChip, MulConfig};
+use crate::parse::field_to_decimal;
+use alloc::{format, string::String, vec, vec::Vec};
+use core::{fmt, marker::PhantomData};
+use halo2::{
+    circuit::*,
+    halo2curves::{bn256::Fr, ff::PrimeField},
+    plonk::*,
+};
 
 // Generate halo2 zkp proof for n-th power of an integer.
 // More formally, it prove the relation R = { ( x, y; exp): x^exp = y } where public input x,y and private input exp.
@@ -13,11 +22,143 @@ pub struct PowerByNumConfig {
     pub col_c: Column<Advice>,
     pub selector: Selector,
     pub instance: Column<Instance>,
+    // set when the output goes to an instance column of its own, apart from the base
+    pub output_instance: Option<Column<Instance>>,
     pub constant: Column<Fixed>,
 }
 
+// Lists the columns with their index and role, one line per kind, e.g. for a learner reading
+// the layout without going through `configure`.
+impl fmt::Display for PowerByNumConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "advice: col_a = advice[{}] (previous power), col_b = advice[{}] (base), \
+             col_c = advice[{}] (a * b)",
+            self.col_a.index(),
+            self.col_b.index(),
+            self.col_c.index()
+        )?;
+        writeln!(f, "selector: mul (a * b = c on enabled rows)")?;
+        match self.output_instance {
+            Some(output) => writeln!(
+                f,
+                "instance: public = instance[{}] (x), output = instance[{}] (y)",
+                self.instance.index(),
+                output.index()
+            )?,
+            None => writeln!(
+                f,
+                "instance: public = instance[{}] (x at row 0, y at row 1)",
+                self.instance.index()
+            )?,
+        }
+        write!(
+            f,
+            "fixed: constant = fixed[{}] (the 1 of the first row)",
+            self.constant.index()
+        )
+    }
+}
+
+// Builds a PowerByNumConfig out of columns owned by a larger circuit.
+// Columns that are not provided are allocated by `build`, in the order a, b, c, instance,
+// constant, so the default builder lays out the same circuit as `PowerByNumChip::configure`.
+// The separate output instance column is only used when given.
+#[derive(Debug, Clone, Default)]
+pub struct PowerByNumConfigBuilder {
+    col_a: Option<Column<Advice>>,
+    col_b: Option<Column<Advice>>,
+    col_c: Option<Column<Advice>>,
+    instance: Option<Column<Instance>>,
+    output_instance: Option<Column<Instance>>,
+    constant: Option<Column<Fixed>>,
+}
+
+impl PowerByNumConfigBuilder {
+    pub fn col_a(mut self, column: Column<Advice>) -> Self {
+        self.col_a = Some(column);
+        self
+    }
+
+    pub fn col_b(mut self, column: Column<Advice>) -> Self {
+        self.col_b = Some(column);
+        self
+    }
+
+    pub fn col_c(mut self, column: Column<Advice>) -> Self {
+        self.col_c = Some(column);
+        self
+    }
+
+    pub fn instance(mut self, column: Column<Instance>) -> Self {
+        self.instance = Some(column);
+        self
+    }
+
+    pub fn output_instance(mut self, column: Column<Instance>) -> Self {
+        self.output_instance = Some(column);
+        self
+    }
+
+    pub fn constant(mut self, column: Column<Fixed>) -> Self {
+        self.constant = Some(column);
+        self
+    }
+
+    pub fn build<F: PrimeField>(self, meta: &mut ConstraintSystem<F>) -> PowerByNumConfig {
+        let col_a = self.col_a.unwrap_or_else(|| meta.advice_column());
+        let col_b = self.col_b.unwrap_or_else(|| meta.advice_column());
+        let col_c = self.col_c.unwrap_or_else(|| meta.advice_column());
+        let instance = self.instance.unwrap_or_else(|| meta.instance_column());
+        let constant = self.constant.unwrap_or_else(|| meta.fixed_column());
+
+        // the "mul" gate and the equality on the advice columns come from the mul chip
+        let MulConfig { selector, .. } = MulChip::configure(meta, col_a, col_b, col_c);
+
+        // columns shared with other chips may already have equality or be the constant column
+        enable_equality_once(meta, instance);
+        if let Some(output_instance) = self.output_instance {
+            enable_equality_once(meta, output_instance);
+        }
+        if !meta.constants().contains(&constant) {
+            meta.enable_constant(constant);
+        }
+
+        PowerByNumConfig {
+            col_a,
+            col_b,
+            col_c,
+            selector,
+            instance,
+            output_instance: self.output_instance,
+            constant,
+        }
+    }
+}
+
+// The columns a parent chip lends to `PowerByNumChip::configure_in`. Several power chips can be
+// configured on the same set, each adding only its own mul selector.
+#[derive(Debug, Clone, Copy)]
+pub struct SharedColumns {
+    pub col_a: Column<Advice>,
+    pub col_b: Column<Advice>,
+    pub col_c: Column<Advice>,
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
+}
+
+// How a chain goes from one row to the next: multiplying by the fixed base computes
+// x, x^2, x^3, ..., squaring computes x, x^2, x^4, x^8, ...
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChainMode {
+    #[default]
+    MultiplyByBase,
+    Square,
+}
+
 #[derive(Debug, Clone)]
-struct PowerByNumChip<F: PrimeField> {
+pub struct PowerByNumChip<F: PrimeField> {
     config: PowerByNumConfig,
     _marker: PhantomData<F>,
 }
@@ -31,99 +172,171 @@ impl<F: PrimeField> PowerByNumChip<F> {
     }
 
     pub fn configure(meta: &mut ConstraintSystem<F>) -> PowerByNumConfig {
-        let col_a = meta.advice_column();
-        let col_b = meta.advice_column();
-        let col_c = meta.advice_column();
-        let selector = meta.selector();
-        let instance = meta.instance_column();
-        let constant = meta.fixed_column();
-
-        meta.enable_equality(col_a);
-        meta.enable_equality(col_b);
-        meta.enable_equality(col_c);
-        meta.enable_equality(instance);
-        meta.enable_constant(constant);
-
-        meta.create_gate("mul", |meta| {
-            let s = meta.query_selector(selector);
-            let a = meta.query_advice(col_a, Rotation::cur());
-            let b = meta.query_advice(col_b, Rotation::cur());
-            let c = meta.query_advice(col_c, Rotation::cur());
-            vec![s * (a * b - c)]
-        });
+        PowerByNumConfigBuilder::default().build(meta)
+    }
 
-        PowerByNumConfig {
-            col_a,
-            col_b,
-            col_c,
-            selector,
-            instance,
-            constant,
-        }
+    // Configures the chip on columns owned by a parent chip, allocating none of its own.
+    pub fn configure_in(meta: &mut ConstraintSystem<F>, shared: SharedColumns) -> PowerByNumConfig {
+        PowerByNumConfigBuilder::default()
+            .col_a(shared.col_a)
+            .col_b(shared.col_b)
+            .col_c(shared.col_c)
+            .instance(shared.instance)
+            .constant(shared.constant)
+            .build(meta)
     }
 
     pub fn intial_assign(
+        &self,
+        layouter: impl Layouter<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), PowerError> {
+        self.initial_assign_from_row(layouter, 0)
+    }
+
+    // Same as `intial_assign` with the base read from instance row `base_row`.
+    pub fn initial_assign_from_row(
         &self,
         mut layouter: impl Layouter<F>,
-    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
-        layouter.assign_region(
-            || "first region",
-            |mut region| {
-                self.config.selector.enable(&mut region, 0)?;
-
-                let init_a = region.assign_advice_from_constant(
-                    || "constant",
-                    self.config.col_a,
-                    0,
-                    F::from(1),
-                )?;
+        base_row: usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), PowerError> {
+        layouter
+            .assign_region(
+                || "first region",
+                |mut region| {
+                    self.config.selector.enable(&mut region, 0)?;
 
-                let init_b = region.assign_advice_from_instance(
-                    || "instance",
-                    self.config.instance,
-                    0,
-                    self.config.col_b,
-                    0,
-                )?;
+                    let init_a = region.assign_advice_from_constant(
+                        || "constant",
+                        self.config.col_a,
+                        0,
+                        F::from(1),
+                    )?;
 
-                let init_c = region.assign_advice(
-                    || "init_a * init_b",
-                    self.config.col_c,
-                    0,
-                    || init_a.value().copied() * init_b.value(),
-                )?;
+                    let init_b = region.assign_advice_from_instance(
+                        || "instance",
+                        self.config.instance,
+                        base_row,
+                        self.config.col_b,
+                        0,
+                    )?;
 
-                Ok((init_a, init_b, init_c))
-            },
-        )
+                    let init_c = region.assign_advice(
+                        || "init_a * init_b",
+                        self.config.col_c,
+                        0,
+                        || init_a.value().copied() * init_b.value(),
+                    )?;
+
+                    Ok((init_a, init_b, init_c))
+                },
+            )
+            .map_err(PowerError::InitialAssign)
     }
 
-    pub fn subsequent_assign(
+    // Same as `intial_assign` but the base is a private witness instead of an instance cell.
+    pub fn private_initial_assign(
         &self,
         mut layouter: impl Layouter<F>,
+        base: Value<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), PowerError> {
+        layouter
+            .assign_region(
+                || "first region",
+                |mut region| {
+                    self.config.selector.enable(&mut region, 0)?;
+
+                    let init_a = region.assign_advice_from_constant(
+                        || "constant",
+                        self.config.col_a,
+                        0,
+                        F::from(1),
+                    )?;
+
+                    let init_b = region.assign_advice(|| "base", self.config.col_b, 0, || base)?;
+
+                    let init_c = region.assign_advice(
+                        || "init_a * init_b",
+                        self.config.col_c,
+                        0,
+                        || init_a.value().copied() * init_b.value(),
+                    )?;
+
+                    Ok((init_a, init_b, init_c))
+                },
+            )
+            .map_err(PowerError::InitialAssign)
+    }
+
+    // `step` labels the region, "power step {step}", and the error, counting the
+    // multiplications after the first row from 1. In `Square` mode prev_b is not used.
+    pub fn subsequent_assign(
+        &self,
+        layouter: impl Layouter<F>,
+        step: usize,
+        mode: ChainMode,
         prev_b: &AssignedCell<F, F>,
         prev_c: &AssignedCell<F, F>,
-    ) -> Result<AssignedCell<F, F>, Error> {
-        layouter.assign_region(
-            || "subsequent row",
-            |mut region| {
-                self.config.selector.enable(&mut region, 0)?;
-
-                // copy the value from previous region
-                prev_c.copy_advice(|| "a", &mut region, self.config.col_a, 0)?;
-
-                prev_b.copy_advice(|| "b", &mut region, self.config.col_b, 0)?;
-
-                let res_c = region.assign_advice(
-                    || "c",
-                    self.config.col_c,
-                    0,
-                    || prev_b.value().copied() * prev_c.value(),
-                )?;
+    ) -> Result<AssignedCell<F, F>, PowerError> {
+        let factor = match mode {
+            ChainMode::MultiplyByBase => prev_b,
+            ChainMode::Square => prev_c,
+        };
 
-                Ok(res_c)
-            },
-        )
+        // copy the values from previous region and multiply them
+        self.mul_chip()
+            .mul_cells_named(layouter, || format!("power step {step}"), prev_c, factor)
+            .map_err(|source| PowerError::SubsequentAssign { step, source })
+    }
+
+    // `subsequent_assign` with the product read from a cache instead of computed. The mul gate
+    // still checks it, so a stale or tampered cache fails the proof.
+    pub fn subsequent_assign_cached(
+        &self,
+        layouter: impl Layouter<F>,
+        step: usize,
+        prev_b: &AssignedCell<F, F>,
+        prev_c: &AssignedCell<F, F>,
+        cached: Value<F>,
+    ) -> Result<AssignedCell<F, F>, PowerError> {
+        self.mul_chip()
+            .mul_cells_with_product(
+                layouter,
+                || format!("power step {step}"),
+                prev_c,
+                prev_b,
+                cached,
+            )
+            .map_err(|source| PowerError::SubsequentAssign { step, source })
+    }
+
+    fn mul_chip(&self) -> MulChip<F> {
+        MulChip::construct(MulConfig {
+            col_a: self.config.col_a,
+            col_b: self.config.col_b,
+            col_c: self.config.col_c,
+            selector: self.config.selector,
+        })
+    }
+
+    // x^0 = 1 needs no multiplication: the result is taken straight from the constant column.
+    pub fn assign_one(&self, layouter: impl Layouter<F>) -> Result<AssignedCell<F, F>, PowerError> {
+        self.assign_constant(layouter, F::from(1))
+    }
+
+    // A cell fixed to `value` through the constant column, e.g. to expose a structural value.
+    pub fn assign_constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: F,
+    ) -> Result<AssignedCell<F, F>, PowerError> {
+        layouter
+            .assign_region(
+                || "constant",
+                |mut region| {
+                    region.assign_advice_from_constant(|| "constant", self.config.col_c, 0, value)
+                },
+            )
+            .map_err(PowerError::InitialAssign)
     }
 
     pub fn expose_public(
@@ -131,20 +344,285 @@ impl<F: PrimeField> PowerByNumChip<F> {
         mut layouter: impl Layouter<F>,
         cell: &AssignedCell<F, F>,
         row: usize,
-    ) -> Result<(), Error> {
-        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    ) -> Result<(), PowerError> {
+        layouter
+            .constrain_instance(cell.cell(), self.config.instance, row)
+            .map_err(PowerError::Expose)
+    }
+
+    // Independent chains base_i^exp_i, one after the other, each with its own private base.
+    // Returns the results in the order of the bases.
+    pub fn assign_power_vector(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bases: &[Value<F>],
+        exps: &[usize],
+    ) -> Result<Vec<AssignedCell<F, F>>, PowerError> {
+        assert_eq!(bases.len(), exps.len(), "one exponent per base");
+
+        let mut results = Vec::with_capacity(bases.len());
+        for (&base, &exp) in bases.iter().zip(exps) {
+            if exp == 0 {
+                results.push(self.assign_one(layouter.namespace(|| "exp zero"))?);
+                continue;
+            }
+
+            let (_, prev_b, mut prev_c) =
+                self.private_initial_assign(layouter.namespace(|| "first region"), base)?;
+            for i in 1..exp {
+                prev_c = self.subsequent_assign(
+                    layouter.namespace(|| "subsequent region"),
+                    i,
+                    ChainMode::MultiplyByBase,
+                    &prev_b,
+                    &prev_c,
+                )?;
+            }
+            results.push(prev_c);
+        }
+        Ok(results)
+    }
+
+    // Like `expose_public`, but on the output instance column when the config has one.
+    pub fn expose_output(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), PowerError> {
+        let column = self.config.output_instance.unwrap_or(self.config.instance);
+        layouter
+            .constrain_instance(cell.cell(), column, row)
+            .map_err(PowerError::Expose)
+    }
+
+    // Constrains each cell to consecutive instance rows starting at `start_row`.
+    pub fn expose_all_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: &[AssignedCell<F, F>],
+        start_row: usize,
+    ) -> Result<(), PowerError> {
+        for (i, cell) in cells.iter().enumerate() {
+            layouter
+                .constrain_instance(cell.cell(), self.config.instance, start_row + i)
+                .map_err(PowerError::Expose)?;
+        }
+        Ok(())
+    }
+}
+
+// Natively computes base^exp, i.e. the value the power chain ends up exposing.
+pub fn compute_output<F: PrimeField>(base: F, exp: usize) -> F {
+    base.pow_vartime([exp as u64])
+}
+
+// The intermediate powers x^2, ..., x^exp, i.e. the products of the multiplication steps, as
+// cached for TestCircuitCachedWitness.
+pub fn witness_table<F: PrimeField>(base: F, exp: usize) -> Vec<F> {
+    let mut power = base;
+    (1..exp)
+        .map(|_| {
+            power *= base;
+            power
+        })
+        .collect()
+}
+
+// The rows TestCircuit assigns for base^exp as a table of a, b and c in decimal, e.g. for 2^3:
+//
+//   row | a | b | c
+//   ----+---+---+--
+//     0 | 1 | 2 | 2
+//     1 | 2 | 2 | 4
+//     2 | 4 | 2 | 8
+//
+// Cells a row leaves unassigned are shown as `-`.
+pub fn explain_power<F: PrimeField>(base: F, exp: usize) -> String {
+    let mut meta = ConstraintSystem::default();
+    let config = PowerByNumChip::configure(&mut meta);
+
+    let output = compute_output(base, exp);
+    let trace = trace(&TestCircuit::<F>::new(exp), instances(base, output))
+        .expect("TestCircuit synthesizes for every exponent");
+    let columns = [config.col_a, config.col_b, config.col_c].map(|c| trace.advice_values(c));
+
+    let rows: Vec<[String; 3]> = (0..trace.rows())
+        .map(|row| {
+            [0, 1, 2].map(|i| {
+                columns[i]
+                    .iter()
+                    .find(|&&(assigned, _)| assigned == row)
+                    .map_or_else(|| String::from("-"), |(_, value)| field_to_decimal(value))
+            })
+        })
+        .collect();
+    let widths = [0, 1, 2].map(|i| rows.iter().map(|row| row[i].len()).fold(1, usize::max));
+
+    let mut lines = vec![
+        format!(
+            "row | {:>w0$} | {:>w1$} | {:>w2$}",
+            "a",
+            "b",
+            "c",
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2]
+        ),
+        format!(
+            "----+-{}-+-{}-+-{}",
+            "-".repeat(widths[0]),
+            "-".repeat(widths[1]),
+            "-".repeat(widths[2])
+        ),
+    ];
+    for (row, [a, b, c]) in rows.iter().enumerate() {
+        lines.push(format!(
+            "{:>3} | {:>w0$} | {:>w1$} | {:>w2$}",
+            row,
+            a,
+            b,
+            c,
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2]
+        ));
+    }
+    lines.join("\n")
+}
+
+// Checked before proving: a wrong claimed output only shows up as a failed proof otherwise.
+pub fn assert_instances_consistent<F: PrimeField>(base: F, exp: usize, claimed_output: F) {
+    let output = compute_output(base, exp);
+    assert!(
+        output == claimed_output,
+        "claimed output {:?} is not base^exp = {:?}^{} = {:?}",
+        claimed_output,
+        base,
+        exp,
+        output
+    );
+}
+
+// The instance columns of TestCircuit, in the shape `MockProver::run` takes: one column holding x
+// at row 0, copied in by `intial_assign`, and y at row 1, where `expose_public` puts the output.
+pub fn instances<F: PrimeField>(base: F, output: F) -> Vec<Vec<F>> {
+    vec![vec![base, output]]
+}
+
+// The instance columns of TestCircuitSeparateInstances: [[x], [y]].
+pub fn separate_instances<F: PrimeField>(base: F, output: F) -> Vec<Vec<F>> {
+    vec![vec![base], vec![output]]
+}
+
+// Rows halo2 keeps out of reach at the end of the domain: the blinding factors of the
+// power circuit plus the row used by l_last. They do not depend on the field, so the
+// constraint system is built over bn256 Fr.
+fn reserved_rows() -> usize {
+    let mut meta = ConstraintSystem::<Fr>::default();
+    PowerByNumChip::configure(&mut meta);
+    meta.blinding_factors() + 1
+}
+
+// Rows of a 2^k domain the power chain can be assigned to.
+pub fn usable_rows(k: u32) -> usize {
+    (1 << k) - reserved_rows()
+}
+
+// Rows laid out by TestCircuit: one per multiplication, or the single row holding the
+// constant 1 when exp = 0. See `dry_run` for measuring it on the actual layout.
+pub fn estimate_rows(exp: usize) -> usize {
+    exp.max(1)
+}
+
+// Smallest k whose usable rows hold the whole power chain, one row per multiplication.
+// MockProver refuses domains below 8 rows, hence the lower bound k = 3.
+pub fn min_k(exp: usize) -> u32 {
+    let rows = estimate_rows(exp);
+
+    let mut k = 3;
+    while usable_rows(k) < rows {
+        k += 1;
+    }
+    k
+}
+
+// Shape of the power circuit's constraint system. None of it depends on exp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitStats {
+    pub num_advice: usize,
+    pub num_fixed: usize,
+    pub num_instance: usize,
+    pub num_selectors: usize,
+    pub max_degree: usize,
+    pub num_gates: usize,
+    pub num_lookups: usize,
+}
+
+pub fn circuit_stats<F: PrimeField>() -> CircuitStats {
+    let mut meta = ConstraintSystem::<F>::default();
+    PowerByNumChip::configure(&mut meta);
+
+    CircuitStats {
+        num_advice: meta.num_advice_columns(),
+        num_fixed: meta.num_fixed_columns(),
+        num_instance: meta.num_instance_columns(),
+        num_selectors: meta.num_selectors(),
+        max_degree: meta.degree(),
+        num_gates: meta.gates().len(),
+        num_lookups: meta.lookups().len(),
     }
 }
 
+// The exponent is a structural parameter of the circuit: it fixes the number of
+// multiplication rows, so it is kept by `without_witnesses` and keygen stays stable.
 #[derive(Default, Clone)]
-pub struct TestCircuit<F>(pub PhantomData<F>);
+pub struct TestCircuit<F> {
+    pub exp: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> TestCircuit<F> {
+    pub fn new(exp: usize) -> Self {
+        Self {
+            exp,
+            _marker: PhantomData,
+        }
+    }
+}
+
+// The structural part of TestCircuit, handed to keygen as `Circuit::Params` with the
+// `circuit-params` feature, so the key is made for the exponent the circuit carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PowerParams {
+    pub exp: usize,
+}
+
+impl<F> From<PowerParams> for TestCircuit<F> {
+    fn from(params: PowerParams) -> Self {
+        Self::new(params.exp)
+    }
+}
 
 impl<F: PrimeField> Circuit<F> for TestCircuit<F> {
     type Config = PowerByNumConfig;
     type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = PowerParams;
 
     fn without_witnesses(&self) -> Self {
-        Self::default()
+        Self::new(self.exp)
+    }
+
+    #[cfg(feature = "circuit-params")]
+    fn params(&self) -> Self::Params {
+        PowerParams { exp: self.exp }
+    }
+
+    // the exponent only decides the rows, not the columns and gates
+    #[cfg(feature = "circuit-params")]
+    fn configure_with_params(meta: &mut ConstraintSystem<F>, _: Self::Params) -> Self::Config {
+        PowerByNumChip::configure(meta)
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -158,60 +636,1488 @@ impl<F: PrimeField> Circuit<F> for TestCircuit<F> {
     ) -> Result<(), Error> {
         let chip = PowerByNumChip::construct(config);
 
-        let (_, prev_b, mut prev_c) = chip.intial_assign(layouter.namespace(|| "first region"))?;
+        // exp = 0 is the boundary case: no multiplication row is laid out and the exposed
+        // result is the constant 1, whatever the base at instance row 0 is.
+        if self.exp == 0 {
+            let one = chip.assign_one(layouter.namespace(|| "exp zero"))?;
+            chip.expose_public(layouter.namespace(|| "out"), &one, 1)?;
+            return Ok(());
+        }
 
-        /* to check the initially assigned values */
-        // println!("{}", format!("{:=<95}", ""));
-        //println!("col_a[0]: {:?}", prev_a.value().copied());
-        // println!("col_b[0]: {:?}", prev_b.value().copied());
-        // println!("col_c[0]: {:?}", prev_c.value().copied());
+        // `explain_power` shows the assigned values row by row
+        let (_, prev_b, mut prev_c) = chip.intial_assign(layouter.namespace(|| "first region"))?;
 
-        for _i in 1..2 {
+        for i in 1..self.exp {
             // store the intended value to a region
-            let tmp_c = chip.subsequent_assign(
+            prev_c = chip.subsequent_assign(
                 layouter.namespace(|| "subsequent region"),
+                i,
+                ChainMode::MultiplyByBase,
                 &prev_b,
                 &prev_c,
             )?;
+        }
+
+        chip.expose_public(layouter.namespace(|| "out"), &prev_c, 1)?;
+
+        Ok(())
+    }
+}
+
+// Proves knowledge of a private x such that x^exp = y, where only y is public (instance row 0).
+// With `reveal_base` the prover also binds x to the instance: [x, y], as for TestCircuit.
+// The flag changes the copy constraints, so it is structural like `exp`.
+#[derive(Default, Clone)]
+pub struct TestCircuitPrivateBase<F> {
+    pub base: Value<F>,
+    pub exp: usize,
+    pub reveal_base: bool,
+}
+
+impl<F> TestCircuitPrivateBase<F> {
+    pub fn new(base: Value<F>, exp: usize) -> Self {
+        Self {
+            base,
+            exp,
+            reveal_base: false,
+        }
+    }
+
+    pub fn revealing_base(base: Value<F>, exp: usize) -> Self {
+        Self {
+            base,
+            exp,
+            reveal_base: true,
+        }
+    }
+}
 
-            /* to check the assigned values */
-            // println!("{}", format!("{:=<95}", ""));
-            // println!("col_a[{}]: {:?}", _i, prev_c.value().copied());
-            // println!("col_b[{}]: {:?}", _i, prev_b.value().copied());
-            // println!("col_c[{}]: {:?}", _i, tmp_c.value().copied());
+impl<F: PrimeField> Circuit<F> for TestCircuitPrivateBase<F> {
+    type Config = PowerByNumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
 
-            prev_c = tmp_c;
+    fn without_witnesses(&self) -> Self {
+        Self {
+            base: Value::unknown(),
+            ..self.clone()
         }
+    }
 
-        // println!("{}", format!("{:=<95}", ""));
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        PowerByNumChip::configure(meta)
+    }
 
-        chip.expose_public(layouter.namespace(|| "out"), &prev_c, 1)?;
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PowerByNumChip::construct(config);
+
+        // the base is witnessed even for exp = 0, where it may still be revealed
+        let (_, prev_b, mut prev_c) =
+            chip.private_initial_assign(layouter.namespace(|| "first region"), self.base)?;
+
+        let out_row = if self.reveal_base {
+            chip.expose_public(layouter.namespace(|| "base"), &prev_b, 0)?;
+            1
+        } else {
+            0
+        };
+
+        // as in TestCircuit, exp = 0 exposes the constant 1 instead of the x^1 of the first row
+        if self.exp == 0 {
+            prev_c = chip.assign_one(layouter.namespace(|| "exp zero"))?;
+        }
+
+        for i in 1..self.exp {
+            prev_c = chip.subsequent_assign(
+                layouter.namespace(|| "subsequent region"),
+                i,
+                ChainMode::MultiplyByBase,
+                &prev_b,
+                &prev_c,
+            )?;
+        }
+
+        chip.expose_public(layouter.namespace(|| "out"), &prev_c, out_row)?;
 
         Ok(())
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use std::marker::PhantomData;
+// Exposes the whole chain x^1, x^2, ..., x^exp to instance rows 1..=exp, prefixed by their
+// count exp at row 0, so the instance is [exp, x, x^2, ..., x^exp]. Row 1 doubles as the base,
+// since x^1 = x. In `Square` mode the powers are x, x^2, x^4, ..., x^(2^(exp - 1)) instead.
+// `all_powers_instances` builds the instance a verifier expects.
+#[derive(Default, Clone)]
+pub struct TestCircuitAllPowers<F> {
+    pub exp: usize,
+    pub mode: ChainMode,
+    _marker: PhantomData<F>,
+}
 
-    use super::TestCircuit;
-    use halo2::{dev::MockProver, halo2curves::bn256::Fr};
+impl<F> TestCircuitAllPowers<F> {
+    pub fn new(exp: usize) -> Self {
+        Self::with_mode(exp, ChainMode::MultiplyByBase)
+    }
 
-    #[test]
-    fn example_test2() {
-        let k = 3;
+    pub fn with_mode(exp: usize, mode: ChainMode) -> Self {
+        Self {
+            exp,
+            mode,
+            _marker: PhantomData,
+        }
+    }
+}
 
-        let input = Fr::from(2); // input x
-        let output = Fr::from(4); // expected result y
+impl<F: PrimeField> Circuit<F> for TestCircuitAllPowers<F> {
+    type Config = PowerByNumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::with_mode(self.exp, self.mode)
+    }
 
-        let circuit = TestCircuit(PhantomData);
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        PowerByNumChip::configure(meta)
+    }
 
-        let public_input = vec![input, output];
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PowerByNumChip::construct(config);
 
-        // runs a synthetic keygen-and-prove operation on the given circuit
-        let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
-        // println!("{:?}", prover);
-        prover.assert_satisfied();
+        // the length is structural, so it is pinned by the constant column
+        let length =
+            chip.assign_constant(layouter.namespace(|| "length"), F::from(self.exp as u64))?;
+        chip.expose_public(layouter.namespace(|| "length"), &length, 0)?;
+
+        let (_, prev_b, init_c) =
+            chip.initial_assign_from_row(layouter.namespace(|| "first region"), 1)?;
+
+        let mut powers = vec![init_c];
+        for i in 1..self.exp {
+            let tmp_c = chip.subsequent_assign(
+                layouter.namespace(|| "subsequent region"),
+                i,
+                self.mode,
+                &prev_b,
+                &powers[powers.len() - 1],
+            )?;
+            powers.push(tmp_c);
+        }
+
+        chip.expose_all_public(layouter.namespace(|| "out"), &powers, 1)?;
+
+        Ok(())
+    }
+}
+
+// The instance of TestCircuitAllPowers: [exp, x, x^2, ..., x^exp], or the squares in `Square`
+// mode. Its length exp + 1 follows from the exponent, so the verifier does not hardcode it.
+pub fn all_powers_instances<F: PrimeField>(base: F, exp: usize, mode: ChainMode) -> Vec<F> {
+    let mut instances = vec![F::from(exp as u64)];
+    let mut power = base;
+    for _ in 0..exp {
+        instances.push(power);
+        power = match mode {
+            ChainMode::MultiplyByBase => power * base,
+            ChainMode::Square => power.square(),
+        };
+    }
+    instances
+}
+
+// Proves x_i^exp_i = y_i for private x_i, with the instance [y_1, y_2, ...]. The exponents
+// are structural, the bases are not.
+#[derive(Default, Clone)]
+pub struct TestCircuitPowerVector<F> {
+    pub bases: Vec<Value<F>>,
+    pub exps: Vec<usize>,
+}
+
+impl<F> TestCircuitPowerVector<F> {
+    pub fn new(bases: Vec<Value<F>>, exps: Vec<usize>) -> Self {
+        Self { bases, exps }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for TestCircuitPowerVector<F> {
+    type Config = PowerByNumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(vec![Value::unknown(); self.bases.len()], self.exps.clone())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        PowerByNumChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PowerByNumChip::construct(config);
+
+        let results =
+            chip.assign_power_vector(layouter.namespace(|| "powers"), &self.bases, &self.exps)?;
+        chip.expose_all_public(layouter.namespace(|| "out"), &results, 0)?;
+
+        Ok(())
+    }
+}
+
+// Proves x^exp_i = y_i for several exponents and one private x.
+// Every chain multiplies by the same base cell, so `mul_cells` copy-constrains all of them
+// to a single witness. `targets` holds (exp, instance_row) pairs and the instance column is
+// [y_1, y_2, ...] in whatever order the rows say.
+#[derive(Default, Clone)]
+pub struct TestCircuitSharedBase<F> {
+    pub base: Value<F>,
+    pub targets: Vec<(usize, usize)>,
+}
+
+impl<F> TestCircuitSharedBase<F> {
+    pub fn new(base: Value<F>, targets: Vec<(usize, usize)>) -> Self {
+        Self { base, targets }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for TestCircuitSharedBase<F> {
+    type Config = PowerByNumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(Value::unknown(), self.targets.clone())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        PowerByNumChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PowerByNumChip::construct(config);
+
+        // the only place the base is witnessed; init_c = 1 * x is x^1
+        let (_, base, init_c) =
+            chip.private_initial_assign(layouter.namespace(|| "shared base"), self.base)?;
+
+        for &(exp, row) in &self.targets {
+            if exp == 0 {
+                let one = chip.assign_one(layouter.namespace(|| "exp zero"))?;
+                chip.expose_public(layouter.namespace(|| "out"), &one, row)?;
+                continue;
+            }
+
+            let mut prev_c = init_c.clone();
+            for i in 1..exp {
+                prev_c = chip.subsequent_assign(
+                    layouter.namespace(|| "subsequent region"),
+                    i,
+                    ChainMode::MultiplyByBase,
+                    &base,
+                    &prev_c,
+                )?;
+            }
+
+            chip.expose_public(layouter.namespace(|| "out"), &prev_c, row)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Proves (x^a)^b = y, i.e. x^(a * b) = y, with two chains in a row.
+// The first chain ends in the x^a cell, which the second one copies in as its base on every
+// multiplication. Instance is [x, y] as for TestCircuit.
+#[derive(Default, Clone)]
+pub struct TestCircuitPowerOfPower<F> {
+    pub a: usize,
+    pub b: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> TestCircuitPowerOfPower<F> {
+    pub fn new(a: usize, b: usize) -> Self {
+        Self {
+            a,
+            b,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for TestCircuitPowerOfPower<F> {
+    type Config = PowerByNumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.a, self.b)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        PowerByNumChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        // a zero exponent anywhere gives 1, as in TestCircuit
+        if self.a == 0 || self.b == 0 {
+            return TestCircuit::<F>::new(0).synthesize(config, layouter);
+        }
+
+        let chip = PowerByNumChip::construct(config);
+
+        let (_, base, mut x_a) = chip.intial_assign(layouter.namespace(|| "first region"))?;
+        for i in 1..self.a {
+            x_a = chip.subsequent_assign(
+                layouter.namespace(|| "inner chain"),
+                i,
+                ChainMode::MultiplyByBase,
+                &base,
+                &x_a,
+            )?;
+        }
+
+        let mut prev_c = x_a.clone();
+        for i in 1..self.b {
+            prev_c = chip.subsequent_assign(
+                layouter.namespace(|| "outer chain"),
+                i,
+                ChainMode::MultiplyByBase,
+                &x_a,
+                &prev_c,
+            )?;
+        }
+
+        chip.expose_public(layouter.namespace(|| "out"), &prev_c, 1)?;
+
+        Ok(())
+    }
+}
+
+// TestCircuit with the base and the output in two instance columns, [[x], [y]], instead of
+// packed into one.
+#[derive(Default, Clone)]
+pub struct TestCircuitSeparateInstances<F> {
+    pub exp: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> TestCircuitSeparateInstances<F> {
+    pub fn new(exp: usize) -> Self {
+        Self {
+            exp,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for TestCircuitSeparateInstances<F> {
+    type Config = PowerByNumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.exp)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        // base first, so the instance columns are passed in the order [x], [y]
+        let instance = meta.instance_column();
+        let output_instance = meta.instance_column();
+        PowerByNumConfigBuilder::default()
+            .instance(instance)
+            .output_instance(output_instance)
+            .build(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PowerByNumChip::construct(config);
+
+        let output = if self.exp == 0 {
+            chip.assign_one(layouter.namespace(|| "exp zero"))?
+        } else {
+            let (_, prev_b, mut prev_c) =
+                chip.intial_assign(layouter.namespace(|| "first region"))?;
+            for i in 1..self.exp {
+                prev_c = chip.subsequent_assign(
+                    layouter.namespace(|| "subsequent region"),
+                    i,
+                    ChainMode::MultiplyByBase,
+                    &prev_b,
+                    &prev_c,
+                )?;
+            }
+            prev_c
+        };
+
+        chip.expose_output(layouter.namespace(|| "out"), &output, 0)?;
+
+        Ok(())
+    }
+}
+
+// TestCircuit with the intermediate powers taken from `powers`, as built by `witness_table`,
+// instead of recomputed. Instance: [x, y].
+#[derive(Default, Clone)]
+pub struct TestCircuitCachedWitness<F> {
+    pub exp: usize,
+    pub powers: Vec<Value<F>>,
+}
+
+impl<F: PrimeField> TestCircuitCachedWitness<F> {
+    pub fn new(exp: usize, powers: &[F]) -> Self {
+        Self {
+            exp,
+            powers: powers.iter().copied().map(Value::known).collect(),
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for TestCircuitCachedWitness<F> {
+    type Config = PowerByNumConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            exp: self.exp,
+            powers: vec![Value::unknown(); self.powers.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        PowerByNumChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PowerByNumChip::construct(config);
+
+        let (_, prev_b, mut prev_c) = chip.intial_assign(layouter.namespace(|| "first region"))?;
+
+        for i in 1..self.exp {
+            // a table too short leaves the product unknown
+            let cached = self
+                .powers
+                .get(i - 1)
+                .copied()
+                .unwrap_or_else(Value::unknown);
+            prev_c = chip.subsequent_assign_cached(
+                layouter.namespace(|| "subsequent region"),
+                i,
+                &prev_b,
+                &prev_c,
+                cached,
+            )?;
+        }
+
+        chip.expose_public(layouter.namespace(|| "out"), &prev_c, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        all_powers_instances, assert_instances_consistent, circuit_stats, compute_output,
+        estimate_rows, explain_power, instances, min_k, separate_instances, usable_rows, ChainMode,
+        CircuitStats, PowerByNumChip, PowerByNumConfig, PowerByNumConfigBuilder, SharedColumns,
+        TestCircuit, TestCircuitAllPowers, TestCircuitPowerOfPower, TestCircuitPowerVector,
+        TestCircuitPrivateBase, TestCircuitSeparateInstances, TestCircuitSharedBase,
+    };
+    use crate::dry_run::dry_run;
+    use crate::end_to_end::prove_verify_kzg;
+    use halo2::{
+        circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::{
+            bn256::Fr,
+            ff::{Field, FromUniformBytes, PrimeField},
+            pasta::Fp,
+            secp256k1::Fq,
+        },
+        plonk::{Any, Circuit, Column, ConstraintSystem, Error},
+    };
+
+    // Runs the power circuit under MockProver for any field, so modulus specific bugs show up.
+    fn run_power_test<F: PrimeField + FromUniformBytes<64> + Ord>(
+        base: u64,
+        exp: usize,
+        expected: u64,
+    ) {
+        let circuit = TestCircuit::<F>::new(exp);
+        let public_input = vec![F::from(base), F::from(expected)];
+
+        let prover = MockProver::run(min_k(exp), &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn example_test2() {
+        let k = min_k(2);
+
+        let input = Fr::from(2); // input x
+        let output = Fr::from(4); // expected result y
+
+        let circuit = TestCircuit::new(2);
+
+        let public_input = vec![input, output];
+
+        // runs a synthetic keygen-and-prove operation on the given circuit
+        let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
+        // println!("{:?}", prover);
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn example_test2_variable_exp() {
+        // the same circuit type proves different exponents
+        for (base, exp, result) in [(2, 5, 32), (3, 4, 81)] {
+            let k = min_k(exp);
+            let circuit = TestCircuit::<Fr>::new(exp);
+            let public_input = vec![Fr::from(base), Fr::from(result)];
+
+            let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn example_test2_private_base() {
+        let k = min_k(3);
+
+        // the prover knows x = 2, the verifier only sees y = 8
+        let circuit = TestCircuitPrivateBase::new(Value::known(Fr::from(2)), 3);
+
+        let public_input = vec![Fr::from(8)];
+
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn example_test2_compute_output() {
+        // the native helper and the in-circuit chain agree on the exposed output
+        for exp in 1..=6 {
+            let k = min_k(exp);
+            let input = Fr::from(7);
+            let output = compute_output(input, exp);
+            assert_eq!(output, Fr::from(7u64.pow(exp as u32)));
+
+            let circuit = TestCircuit::new(exp);
+            let prover = MockProver::run(k, &circuit, vec![vec![input, output]]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn example_test2_min_k() {
+        // 1000 rows plus the reserved ones need a 1024-row domain
+        let exp = 1000;
+        let k = min_k(exp);
+        assert_eq!(k, 10);
+
+        let input = Fr::from(2);
+        let output = compute_output(input, exp);
+
+        let circuit = TestCircuit::new(exp);
+        let prover = MockProver::run(k, &circuit, vec![vec![input, output]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn example_test2_field_agnostic() {
+        run_power_test::<Fr>(3, 5, 243);
+        run_power_test::<Fp>(3, 5, 243);
+        run_power_test::<Fq>(3, 5, 243);
+    }
+
+    #[test]
+    fn example_test2_exp_zero() {
+        let k = min_k(0);
+
+        // x^0 = 1 for every base
+        for base in [0, 2, 5, 12345] {
+            let circuit = TestCircuit::new(0);
+            let public_input = vec![Fr::from(base), Fr::from(1)];
+
+            let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn example_test2_private_base_exp_zero() {
+        // the first row and the constant 1, as many rows as x^2
+        let k = min_k(2);
+
+        for base in [0, 2, 5, 12345] {
+            let base = Fr::from(base);
+
+            let circuit = TestCircuitPrivateBase::new(Value::known(base), 0);
+            let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(1)]]).unwrap();
+            prover.assert_satisfied();
+
+            let circuit = TestCircuitPrivateBase::revealing_base(Value::known(base), 0);
+            let prover = MockProver::run(k, &circuit, vec![vec![base, Fr::from(1)]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // x^0 is not x
+        let circuit = TestCircuitPrivateBase::new(Value::known(Fr::from(2)), 0);
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(2)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // the shared base circuit too
+        let circuit = TestCircuitSharedBase::new(Value::known(Fr::from(2)), vec![(0, 0)]);
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(1)]]).unwrap();
+        prover.assert_satisfied();
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(2)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn example_test2_all_powers() {
+        let exp = 4;
+
+        let circuit = TestCircuitAllPowers::new(exp);
+        let public_input = vec![
+            Fr::from(4),
+            Fr::from(2),
+            Fr::from(4),
+            Fr::from(8),
+            Fr::from(16),
+        ];
+
+        let prover = MockProver::run(min_k(exp), &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn example_test2_shared_base() {
+        // one row for the shared x^1, then exp - 1 rows per chain
+        let k = min_k(1 + 2 + 4);
+
+        // 2^3 = 8 and 2^5 = 32 for the same private x
+        let circuit = TestCircuitSharedBase::new(Value::known(Fr::from(2)), vec![(3, 0), (5, 1)]);
+        let public_input = vec![Fr::from(8), Fr::from(32)];
+
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // Allocates the columns itself, as a larger circuit would, and hands them to the builder.
+    // The constant column is left to the builder.
+    #[derive(Default)]
+    struct ExternalColumnsCircuit {
+        exp: usize,
+    }
+
+    impl Circuit<Fr> for ExternalColumnsCircuit {
+        type Config = PowerByNumConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        #[cfg(feature = "circuit-params")]
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self { exp: self.exp }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let instance = meta.instance_column();
+            let advice = [(); 3].map(|_| meta.advice_column());
+
+            let config = PowerByNumConfigBuilder::default()
+                .col_a(advice[0])
+                .col_b(advice[1])
+                .col_c(advice[2])
+                .instance(instance)
+                .build(meta);
+
+            assert_eq!([config.col_a, config.col_b, config.col_c], advice);
+            assert_eq!(config.instance, instance);
+            assert_eq!(config.output_instance, None);
+            config
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            TestCircuit::<Fr>::new(self.exp).synthesize(config, layouter)
+        }
+    }
+
+    #[test]
+    fn example_test2_config_builder() {
+        let exp = 5;
+
+        let circuit = ExternalColumnsCircuit { exp };
+        let public_input = vec![Fr::from(2), Fr::from(32)];
+
+        let prover = MockProver::run(min_k(exp), &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    // Two chains x1^exp1 and x2^exp2 on the same columns. Instance: [x1, y1, x2, y2].
+    #[derive(Default)]
+    struct SharedColumnsCircuit {
+        exps: [usize; 2],
+    }
+
+    impl Circuit<Fr> for SharedColumnsCircuit {
+        type Config = [PowerByNumConfig; 2];
+        type FloorPlanner = SimpleFloorPlanner;
+        #[cfg(feature = "circuit-params")]
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self { exps: self.exps }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let shared = SharedColumns {
+                col_a: meta.advice_column(),
+                col_b: meta.advice_column(),
+                col_c: meta.advice_column(),
+                instance: meta.instance_column(),
+                constant: meta.fixed_column(),
+            };
+
+            [
+                PowerByNumChip::configure_in(meta, shared),
+                PowerByNumChip::configure_in(meta, shared),
+            ]
+        }
+
+        fn synthesize(
+            &self,
+            configs: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            for (i, (config, exp)) in configs.into_iter().zip(self.exps).enumerate() {
+                let chip = PowerByNumChip::construct(config);
+
+                let (_, base, mut power) =
+                    chip.initial_assign_from_row(layouter.namespace(|| "first region"), 2 * i)?;
+                for step in 1..exp {
+                    power = chip.subsequent_assign(
+                        layouter.namespace(|| "subsequent region"),
+                        step,
+                        ChainMode::MultiplyByBase,
+                        &base,
+                        &power,
+                    )?;
+                }
+                chip.expose_public(layouter.namespace(|| "out"), &power, 2 * i + 1)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn example_test2_shared_columns() {
+        // no columns beyond the shared ones, and the equality set holds each column once, the
+        // constant column included
+        let mut meta = ConstraintSystem::<Fr>::default();
+        SharedColumnsCircuit::configure(&mut meta);
+        assert_eq!(meta.num_advice_columns(), 3);
+        assert_eq!(meta.num_instance_columns(), 1);
+        assert_eq!(meta.num_fixed_columns(), 1);
+        assert_eq!(meta.permutation().get_columns().len(), 5);
+        assert_eq!(meta.constants().len(), 1);
+
+        // 2^3 = 8 and 3^4 = 81
+        let circuit = SharedColumnsCircuit { exps: [3, 4] };
+        let public_input = vec![Fr::from(2), Fr::from(8), Fr::from(3), Fr::from(81)];
+        let prover = MockProver::run(min_k(7), &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // the second statement is checked by the second chip
+        let public_input = vec![Fr::from(2), Fr::from(8), Fr::from(3), Fr::from(27)];
+        let prover = MockProver::run(min_k(7), &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn example_test2_explain() {
+        let table = explain_power(Fr::from(2), 4);
+        let rows: Vec<Vec<&str>> = table
+            .lines()
+            .skip(2)
+            .map(|line| line.split('|').map(str::trim).collect())
+            .collect();
+
+        // x^i in c, fed back into a, with the base in b
+        assert_eq!(
+            rows,
+            vec![
+                vec!["0", "1", "2", "2"],
+                vec!["1", "2", "2", "4"],
+                vec!["2", "4", "2", "8"],
+                vec!["3", "8", "2", "16"],
+            ]
+        );
+        assert!(table.starts_with("row | a | b |  c\n----+---+---+---\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "is not base^exp")]
+    fn example_test2_inconsistent_instances() {
+        assert_instances_consistent(Fr::from(2), 3, Fr::from(8));
+
+        // 2^2 = 4, not 5
+        assert_instances_consistent(Fr::from(2), 2, Fr::from(5));
+    }
+
+    #[test]
+    fn example_test2_power_of_power() {
+        let input = Fr::from(2);
+        let output = Fr::from(64);
+        assert_eq!(compute_output(compute_output(input, 2), 3), output);
+        assert_eq!(compute_output(input, 6), output);
+
+        // (2^2)^3 = 64, and the plain chain agrees on 2^6
+        let circuit = TestCircuitPowerOfPower::new(2, 3);
+        let prover = MockProver::run(min_k(4), &circuit, vec![vec![input, output]]).unwrap();
+        prover.assert_satisfied();
+
+        let circuit = TestCircuit::new(6);
+        let prover = MockProver::run(min_k(6), &circuit, vec![vec![input, output]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn example_test2_usable_rows() {
+        let k = 4;
+        let exp = usable_rows(k);
+        assert_eq!(estimate_rows(exp), exp);
+        assert_eq!(min_k(exp), k);
+
+        // a chain exactly filling the usable rows still fits
+        let input = Fr::from(2);
+        let circuit = TestCircuit::new(exp);
+        let public_input = vec![input, compute_output(input, exp)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // one more row needs the next k
+        let exp = exp + 1;
+        assert_eq!(min_k(exp), k + 1);
+
+        let circuit = TestCircuit::new(exp);
+        let public_input = vec![input, compute_output(input, exp)];
+        assert!(MockProver::run(k, &circuit, vec![public_input.clone()]).is_err());
+        let prover = MockProver::run(k + 1, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn example_test2_reveal_base() {
+        let k = min_k(3);
+        let circuit = TestCircuitPrivateBase::revealing_base(Value::known(Fr::from(2)), 3);
+
+        // the revealed base sits at row 0, before y
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(2), Fr::from(8)]]).unwrap();
+        prover.assert_satisfied();
+
+        // y alone is not enough, and neither is another base
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(8)]]).unwrap();
+        assert!(prover.verify().is_err());
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(3), Fr::from(8)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn example_test2_constraint_system() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let config = TestCircuit::<Fr>::configure(&mut meta);
+
+        assert_eq!(meta.num_advice_columns(), 3);
+        assert_eq!(meta.num_instance_columns(), 1);
+        assert_eq!(meta.num_fixed_columns(), 1);
+        assert_eq!(meta.num_selectors(), 1);
+
+        // s * (a * b - c) has degree 3: a * b is quadratic and the selector adds one. The
+        // permutation argument needs degree 3 as well, so the circuit as a whole stays at 3.
+        let gate_degrees: Vec<_> = meta
+            .gates()
+            .iter()
+            .flat_map(|gate| gate.polynomials())
+            .map(|poly| poly.degree())
+            .collect();
+        assert_eq!(gate_degrees, [3]);
+        assert_eq!(meta.degree(), 3);
+
+        // the constant column takes part in the permutation through `enable_constant`
+        let mut columns = meta.permutation().get_columns();
+        let mut expected: Vec<Column<Any>> = vec![
+            config.col_a.into(),
+            config.col_b.into(),
+            config.col_c.into(),
+            config.instance.into(),
+            config.constant.into(),
+        ];
+        columns.sort();
+        expected.sort();
+        assert_eq!(columns, expected);
+    }
+
+    #[test]
+    fn example_test2_chain_modes() {
+        let k = min_k(4);
+        let input = Fr::from(2);
+
+        // 2, 4, 8, 16
+        let circuit = TestCircuitAllPowers::with_mode(4, ChainMode::MultiplyByBase);
+        let public_input = [4u64, 2, 4, 8, 16].map(Fr::from).to_vec();
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // 2, 4, 16, 256
+        let circuit = TestCircuitAllPowers::with_mode(4, ChainMode::Square);
+        let public_input = [4u64, 2, 4, 16, 256].map(Fr::from).to_vec();
+        assert_eq!(public_input[4], compute_output(input, 8));
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn example_test2_separate_instances() {
+        let exp = 5;
+        let circuit = TestCircuitSeparateInstances::<Fr>::new(exp);
+
+        // 2^5 = 32, base and output each in a column of their own
+        let public_input = separate_instances(Fr::from(2), Fr::from(32));
+        let prover = MockProver::run(min_k(exp), &circuit, public_input).unwrap();
+        prover.assert_satisfied();
+
+        // the packed layout of TestCircuit does not fit: the output column is read at row 0
+        let public_input = vec![vec![Fr::from(2), Fr::from(32)], vec![Fr::from(0)]];
+        let prover = MockProver::run(min_k(exp), &circuit, public_input).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // Fails unless the cell holds `expected`, e.g. to check the chain between regions.
+    #[track_caller]
+    fn assert_cell_eq<F: Field>(cell: &AssignedCell<F, F>, expected: F) {
+        let mut actual = None;
+        cell.value().map(|value| actual = Some(*value));
+        match actual {
+            Some(actual) => assert_eq!(actual, expected, "cell holds an unexpected value"),
+            None => panic!("cell value is unknown, expected {:?}", expected),
+        }
+    }
+
+    // Walks the chain of TestCircuit and checks every x^i as it is assigned.
+    #[derive(Default)]
+    struct CheckedChainCircuit {
+        expected: Vec<u64>,
+    }
+
+    impl Circuit<Fr> for CheckedChainCircuit {
+        type Config = PowerByNumConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        #[cfg(feature = "circuit-params")]
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                expected: self.expected.clone(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            PowerByNumChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let chip = PowerByNumChip::construct(config);
+
+            let (prev_a, prev_b, mut prev_c) =
+                chip.intial_assign(layouter.namespace(|| "first region"))?;
+            assert_cell_eq(&prev_a, Fr::ONE);
+            assert_cell_eq(&prev_b, Fr::from(self.expected[0]));
+            assert_cell_eq(&prev_c, Fr::from(self.expected[0]));
+
+            for (i, &expected) in self.expected.iter().enumerate().skip(1) {
+                prev_c = chip.subsequent_assign(
+                    layouter.namespace(|| "subsequent region"),
+                    i,
+                    ChainMode::MultiplyByBase,
+                    &prev_b,
+                    &prev_c,
+                )?;
+                assert_cell_eq(&prev_c, Fr::from(expected));
+            }
+
+            chip.expose_public(layouter.namespace(|| "out"), &prev_c, 1)?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn example_test2_intermediate_values() {
+        // 2, 4, 8, 16 on the way to 2^4
+        let circuit = CheckedChainCircuit {
+            expected: vec![2, 4, 8, 16],
+        };
+        let public_input = vec![Fr::from(2), Fr::from(16)];
+
+        let prover = MockProver::run(min_k(4), &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn example_test2_circuit_stats() {
+        let stats = circuit_stats::<Fr>();
+        assert_eq!(
+            stats,
+            CircuitStats {
+                num_advice: 3,
+                num_fixed: 1,
+                num_instance: 1,
+                num_selectors: 1,
+                max_degree: 3,
+                num_gates: 1,
+                num_lookups: 0,
+            }
+        );
+
+        // the field does not change the layout
+        assert_eq!(circuit_stats::<Fp>(), stats);
+    }
+
+    #[test]
+    fn example_test2_all_powers_instances() {
+        let exp = 6;
+        let input = Fr::from(3);
+
+        let public_input = all_powers_instances(input, exp, ChainMode::MultiplyByBase);
+        assert_eq!(public_input.len(), exp + 1);
+        assert_eq!(public_input[exp], compute_output(input, exp));
+
+        let circuit = TestCircuitAllPowers::<Fr>::new(exp);
+        let prover = MockProver::run(min_k(exp + 1), &circuit, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+        assert!(prove_verify_kzg(min_k(exp + 1), circuit, &[&public_input]));
+
+        // a verifier assuming another exponent builds an instance the proof does not match
+        let circuit = TestCircuitAllPowers::<Fr>::new(exp);
+        let public_input = all_powers_instances(input, exp - 1, ChainMode::MultiplyByBase);
+        let prover = MockProver::run(min_k(exp + 1), &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn example_test2_power_vector() {
+        // [2^3, 3^2, 5^2] = [8, 9, 25], one row per multiplication
+        let k = min_k(3 + 2 + 2);
+        let bases = [2u64, 3, 5]
+            .map(|base| Value::known(Fr::from(base)))
+            .to_vec();
+        let circuit = TestCircuitPowerVector::new(bases, vec![3, 2, 2]);
+
+        let public_input = [8u64, 9, 25].map(Fr::from).to_vec();
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // the outputs are bound in order
+        let public_input = [9u64, 8, 25].map(Fr::from).to_vec();
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[cfg(feature = "circuit-params")]
+    #[test]
+    fn example_test2_circuit_params() {
+        use super::PowerParams;
+
+        // one circuit type, the exponent picked through its params
+        for exp in [1, 3, 5] {
+            let params = PowerParams { exp };
+            let circuit = TestCircuit::<Fr>::from(params);
+            assert_eq!(circuit.params(), params);
+
+            let input = Fr::from(2);
+            let public_input = vec![input, compute_output(input, exp)];
+            let prover = MockProver::run(min_k(exp), &circuit, vec![public_input]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    // Gate coverage: a region that forgets `selector.enable` leaves its row unconstrained, so
+    // every multiplication must show up as one enabled row of the mul selector.
+    #[track_caller]
+    fn assert_mul_rows<C: Circuit<Fr, Config = PowerByNumConfig>>(circuit: &C, expected: usize) {
+        let mut meta = ConstraintSystem::default();
+        let config = C::configure(&mut meta);
+        let dry_run = dry_run(circuit).expect("dry run failed");
+        assert_eq!(dry_run.enabled_rows(&config.selector), expected);
+    }
+
+    #[test]
+    fn example_test2_gate_coverage() {
+        let base = Fr::from(2);
+        for exp in [1, 2, 5, 13] {
+            let circuit = TestCircuit::<Fr>::new(exp);
+            let public_input = vec![base, compute_output(base, exp)];
+            let prover = MockProver::run(min_k(exp), &circuit, vec![public_input]).unwrap();
+            prover.assert_satisfied();
+
+            assert_mul_rows(&circuit, exp);
+        }
+
+        // x^0 = 1 is a constant, no multiplication takes place
+        assert_mul_rows(&TestCircuit::<Fr>::new(0), 0);
+    }
+    #[test]
+    fn example_test2_instances() {
+        let exp = 5;
+        let (input, output) = (Fr::from(3), compute_output(Fr::from(3), exp));
+
+        let circuit = TestCircuit::new(exp);
+        let prover = MockProver::run(min_k(exp), &circuit, instances(input, output)).unwrap();
+        prover.assert_satisfied();
+
+        let circuit = TestCircuitSeparateInstances::new(exp);
+        let public_input = separate_instances(input, output);
+        let prover = MockProver::run(min_k(exp), &circuit, public_input).unwrap();
+        prover.assert_satisfied();
+
+        // the same columns go to create_proof as slices
+        let public_input = instances(input, output);
+        let columns: Vec<&[Fr]> = public_input.iter().map(Vec::as_slice).collect();
+        assert!(prove_verify_kzg(
+            min_k(exp),
+            TestCircuit::new(exp),
+            &columns
+        ));
+    }
+    #[test]
+    fn example_test2_config_display() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let description = PowerByNumChip::configure(&mut meta).to_string();
+
+        for name in ["col_a", "col_b", "col_c", "mul", "public", "constant"] {
+            assert!(
+                description.contains(name),
+                "{name} missing from {description}"
+            );
+        }
+        assert!(description.contains("col_c = advice[2]"));
+        assert!(!description.contains("output"));
+
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let output = meta.instance_column();
+        let config = PowerByNumConfigBuilder::default()
+            .output_instance(output)
+            .build(&mut meta);
+        assert!(config.to_string().contains("output = instance[0] (y)"));
+    }
+}
+
+// Bad witnesses have to be rejected: these tests pin down how MockProver reports them.
+#[cfg(test)]
+mod negative_tests {
+    use super::{min_k, ChainMode, PowerByNumChip, PowerByNumConfig, TestCircuit};
+    use crate::dry_run::dry_run;
+    use halo2::{
+        circuit::*,
+        dev::{FailureLocation, MockProver, VerifyFailure},
+        halo2curves::bn256::Fr,
+        plonk::*,
+    };
+
+    // Lays out 2^2 like TestCircuit but witnesses a wrong product in the second mul row.
+    #[derive(Default)]
+    struct WrongProductCircuit;
+
+    impl Circuit<Fr> for WrongProductCircuit {
+        type Config = PowerByNumConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        #[cfg(feature = "circuit-params")]
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            PowerByNumChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let chip = PowerByNumChip::construct(config.clone());
+
+            let (_, prev_b, prev_c) = chip.intial_assign(layouter.namespace(|| "first region"))?;
+
+            let res_c = layouter.assign_region(
+                || "wrong row",
+                |mut region| {
+                    config.selector.enable(&mut region, 0)?;
+                    prev_c.copy_advice(|| "a", &mut region, config.col_a, 0)?;
+                    prev_b.copy_advice(|| "b", &mut region, config.col_b, 0)?;
+
+                    // a * b + 1 instead of a * b
+                    region.assign_advice(
+                        || "c",
+                        config.col_c,
+                        0,
+                        || prev_b.value().copied() * prev_c.value() + Value::known(Fr::from(1)),
+                    )
+                },
+            )?;
+
+            chip.expose_public(layouter.namespace(|| "out"), &res_c, 1)?;
+            Ok(())
+        }
+    }
+
+    // Proves 2^3 = 8 honestly, then a second chain 3^2 = 9 whose operands claim to be copies
+    // of the shared base 2.
+    #[derive(Default)]
+    struct InconsistentBaseCircuit;
+
+    impl Circuit<Fr> for InconsistentBaseCircuit {
+        type Config = PowerByNumConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        #[cfg(feature = "circuit-params")]
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            PowerByNumChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let chip = PowerByNumChip::construct(config.clone());
+
+            let (_, base, mut prev_c) = chip.private_initial_assign(
+                layouter.namespace(|| "shared base"),
+                Value::known(Fr::from(2)),
+            )?;
+            for i in 1..3 {
+                prev_c = chip.subsequent_assign(
+                    layouter.namespace(|| "subsequent region"),
+                    i,
+                    ChainMode::MultiplyByBase,
+                    &base,
+                    &prev_c,
+                )?;
+            }
+            chip.expose_public(layouter.namespace(|| "out"), &prev_c, 0)?;
+
+            let other = layouter.assign_region(
+                || "other base",
+                |mut region| {
+                    config.selector.enable(&mut region, 0)?;
+                    let three = Value::known(Fr::from(3));
+                    let a = region.assign_advice(|| "a", config.col_a, 0, || three)?;
+                    let b = region.assign_advice(|| "b", config.col_b, 0, || three)?;
+                    region.constrain_equal(a.cell(), base.cell())?;
+                    region.constrain_equal(b.cell(), base.cell())?;
+
+                    region.assign_advice(|| "c", config.col_c, 0, || three * three)
+                },
+            )?;
+            chip.expose_public(layouter.namespace(|| "out"), &other, 1)?;
+            Ok(())
+        }
+    }
+
+    // Proves 2^2 = 4 like TestCircuit, plus a region holding 2 * 3 = 7 that belongs to no chain.
+    // The mul selector is only enabled on that row with `enable_selector`.
+    #[derive(Default)]
+    struct GarbageRowCircuit {
+        enable_selector: bool,
+    }
+
+    impl Circuit<Fr> for GarbageRowCircuit {
+        type Config = PowerByNumConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        #[cfg(feature = "circuit-params")]
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                enable_selector: self.enable_selector,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            PowerByNumChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            TestCircuit::<Fr>::new(2).synthesize(config.clone(), layouter.namespace(|| "2^2"))?;
+
+            layouter.assign_region(
+                || "garbage",
+                |mut region| {
+                    if self.enable_selector {
+                        config.selector.enable(&mut region, 0)?;
+                    }
+                    region.assign_advice(|| "a", config.col_a, 0, || Value::known(Fr::from(2)))?;
+                    region.assign_advice(|| "b", config.col_b, 0, || Value::known(Fr::from(3)))?;
+                    region.assign_advice(|| "c", config.col_c, 0, || Value::known(Fr::from(7)))?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn rows_without_selector_are_unconstrained() {
+        let public_input = vec![Fr::from(2), Fr::from(4)];
+
+        let circuit = GarbageRowCircuit {
+            enable_selector: false,
+        };
+        let prover = MockProver::run(min_k(3), &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn rows_without_selector_show_in_coverage() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let config = GarbageRowCircuit::configure(&mut meta);
+
+        // the two rows of 2^2, plus the garbage row when its selector is on
+        for (enable_selector, rows) in [(false, 2), (true, 3)] {
+            let dry_run = dry_run(&GarbageRowCircuit { enable_selector }).unwrap();
+            assert_eq!(dry_run.enabled_rows(&config.selector), rows);
+        }
+    }
+
+    #[test]
+    fn rows_with_selector_are_constrained() {
+        let public_input = vec![Fr::from(2), Fr::from(4)];
+
+        // the same garbage fails the mul gate once the selector is on
+        let circuit = GarbageRowCircuit {
+            enable_selector: true,
+        };
+        let prover = MockProver::run(min_k(3), &circuit, vec![public_input]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert_eq!(failures.len(), 1);
+        assert!(matches!(
+            &failures[0],
+            VerifyFailure::ConstraintNotSatisfied { constraint, .. }
+                if constraint.to_string().contains("mul")
+        ));
+    }
+
+    #[test]
+    fn wrong_output_is_rejected() {
+        // claims 2^2 = 5: the honest chain computes 4, so the copy to the instance fails
+        let circuit = TestCircuit::new(2);
+        let public_input = vec![Fr::from(2), Fr::from(5)];
+
+        let prover = MockProver::run(min_k(2), &circuit, vec![public_input]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
+    }
+
+    #[test]
+    fn failing_step_is_named() {
+        // claims 2^4 = 17: the mismatch is reported in the region of the last multiplication
+        let circuit = TestCircuit::new(4);
+        let public_input = vec![Fr::from(2), Fr::from(17)];
+
+        let prover = MockProver::run(min_k(4), &circuit, vec![public_input]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(failures.iter().any(|failure| matches!(
+            failure,
+            VerifyFailure::Permutation {
+                location: FailureLocation::InRegion { .. },
+                ..
+            } if failure.to_string().contains("'power step 3'")
+        )));
+    }
+
+    #[test]
+    fn wrong_intermediate_is_rejected() {
+        // the witnessed product matches the claimed output 5, but not the mul gate
+        let public_input = vec![Fr::from(2), Fr::from(5)];
+
+        let prover = MockProver::run(min_k(2), &WrongProductCircuit, vec![public_input]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(failures.iter().any(|failure| matches!(
+            failure,
+            VerifyFailure::ConstraintNotSatisfied { constraint, .. }
+                if constraint.to_string().contains("mul")
+        )));
+    }
+
+    #[test]
+    fn inconsistent_bases_are_rejected() {
+        // every gate holds, only the copies to the shared base break
+        let public_input = vec![Fr::from(8), Fr::from(9)];
+
+        let prover =
+            MockProver::run(min_k(4), &InconsistentBaseCircuit, vec![public_input]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(failures
+            .iter()
+            .all(|failure| matches!(failure, VerifyFailure::Permutation { .. })));
     }
 }
@@ -0,0 +1,101 @@
+// Same circuit as example2, proven with the IPA commitment scheme of the PSE library.
+// Only the commitment machinery changes: no trusted setup (`ParamsIPA::new` derives its generators
+// from a hash), larger proofs and a verifier doing a linear amount of work in 2^k. The pasta
+// Vesta curve is used since IPA needs no pairing.
+use crate::example2::TestCircuit;
+use alloc::{vec, vec::Vec};
+use halo2::{
+    halo2curves::pasta::{EqAffine, Fp},
+    plonk::{create_proof, verify_proof, Error, ProvingKey, VerifyingKey},
+    poly::{
+        commitment::ParamsProver,
+        ipa::{
+            commitment::{IPACommitmentScheme, ParamsIPA},
+            multiopen::{ProverIPA, VerifierIPA},
+            strategy::AccumulatorStrategy,
+        },
+        VerificationStrategy,
+    },
+    transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
+    },
+};
+use rand::RngCore;
+
+// Proves base^exp = result for the exponent `pk` was generated for.
+pub fn prove_ipa(
+    params: &ParamsIPA<EqAffine>,
+    pk: &ProvingKey<EqAffine>,
+    base: Fp,
+    result: Fp,
+    exp: usize,
+    rng: impl RngCore,
+) -> Result<Vec<u8>, Error> {
+    let circuit = TestCircuit::<Fp>::new(exp);
+    let instances = [base, result];
+
+    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+    create_proof::<IPACommitmentScheme<_>, ProverIPA<_>, _, _, _, _>(
+        params,
+        pk,
+        &[circuit],
+        &[&[&instances]],
+        rng,
+        &mut transcript,
+    )?;
+
+    Ok(transcript.finalize())
+}
+
+// Same contract as `verify_power_proof`: malformed proofs are `Err`, invalid ones `Ok(false)`.
+pub fn verify_ipa(
+    params: &ParamsIPA<EqAffine>,
+    vk: &VerifyingKey<EqAffine>,
+    instances: &[Fp],
+    proof: &[u8],
+) -> Result<bool, Error> {
+    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+    let strategy = verify_proof::<IPACommitmentScheme<_>, VerifierIPA<_>, _, _, _>(
+        params.verifier_params(),
+        vk,
+        AccumulatorStrategy::new(params.verifier_params()),
+        &[&[instances]],
+        &mut transcript,
+    )?;
+
+    Ok(VerificationStrategy::<_, VerifierIPA<_>>::finalize(
+        strategy,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prove_ipa, verify_ipa};
+    use crate::example2::{min_k, TestCircuit};
+    use halo2::{
+        halo2curves::pasta::{EqAffine, Fp},
+        plonk::{keygen_pk, keygen_vk},
+        poly::{commitment::ParamsProver, ipa::commitment::ParamsIPA},
+    };
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn example_ipa_test() {
+        let exp = 3;
+        let params = ParamsIPA::<EqAffine>::new(min_k(exp));
+
+        let circuit = TestCircuit::<Fp>::new(exp);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+
+        // 2^3 = 8
+        let (base, result) = (Fp::from(2), Fp::from(8));
+        let proof =
+            prove_ipa(&params, &pk, base, result, exp, OsRng).expect("proof generation failed");
+        assert!(verify_ipa(&params, pk.get_vk(), &[base, result], &proof).unwrap());
+
+        // the same proof does not pass for another output
+        let result = verify_ipa(&params, pk.get_vk(), &[base, Fp::from(9)], &proof);
+        assert!(!matches!(result, Ok(true)));
+    }
+}
@@ -0,0 +1,82 @@
+// A fixed sequence of operations applied to a public x.
+// It proves the relation R = { ( x, y; ): op_n(... op_1(x)) = y }, e.g. ((x * x) + c) * x = y,
+// by interpreting the ops one region at a time with the gates of the poly_eval chip. The
+// accumulator cell of each step is copied into the next, so the steps form one chain.
+use crate::mul::MulChip;
+use crate::poly_eval::{PolyEvalChip, PolyEvalConfig};
+use alloc::vec::Vec;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Op<F> {
+    // acc * x
+    Mul,
+    // acc + c, with c fixed in the circuit
+    AddConst(F),
+}
+
+// Public instance: [x, y]. The accumulator starts at x, and the ops are part of the circuit.
+#[derive(Default, Clone)]
+pub struct ExpressionCircuit<F> {
+    pub ops: Vec<Op<F>>,
+}
+
+impl<F: PrimeField> Circuit<F> for ExpressionCircuit<F> {
+    type Config = PolyEvalConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        PolyEvalChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PolyEvalChip::construct(config.clone());
+        let mul_chip = MulChip::construct(config.mul);
+
+        let x = chip.load_x(layouter.namespace(|| "x"))?;
+
+        let mut acc = x.clone();
+        for op in &self.ops {
+            acc = match *op {
+                Op::Mul => mul_chip.mul_cells(layouter.namespace(|| "acc * x"), &acc, &x)?,
+                Op::AddConst(c) => chip.add_constant(layouter.namespace(|| "acc + c"), &acc, c)?,
+            };
+        }
+
+        chip.expose_public(layouter.namespace(|| "out"), &acc, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExpressionCircuit, Op};
+    use halo2::{dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn expression_test() {
+        let k = 4;
+
+        // ((3 * 3) + 1) * 3 = 30
+        let circuit = ExpressionCircuit {
+            ops: vec![Op::Mul, Op::AddConst(Fr::from(1)), Op::Mul],
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(3), Fr::from(30)]]).unwrap();
+        prover.assert_satisfied();
+
+        // and 31 is not the result
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(3), Fr::from(31)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
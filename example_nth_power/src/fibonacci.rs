@@ -0,0 +1,231 @@
+// Fibonacci example on the same three-column layout as the power chip.
+// Each row enforces s * (a + b - c) and the next row copies b -> a and c -> b,
+// so it proves the relation R = { ( y; n): F(n) = y } with F(0) = 0, F(1) = 1.
+use alloc::vec;
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct FibonacciConfig {
+    pub col_a: Column<Advice>,
+    pub col_b: Column<Advice>,
+    pub col_c: Column<Advice>,
+    pub selector: Selector,
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FibonacciChip<F: PrimeField> {
+    config: FibonacciConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> FibonacciChip<F> {
+    pub fn construct(config: FibonacciConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> FibonacciConfig {
+        let col_a = meta.advice_column();
+        let col_b = meta.advice_column();
+        let col_c = meta.advice_column();
+        let selector = meta.selector();
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        meta.enable_equality(col_a);
+        meta.enable_equality(col_b);
+        meta.enable_equality(col_c);
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate("add", |meta| {
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            vec![s * (a + b - c)]
+        });
+
+        FibonacciConfig {
+            col_a,
+            col_b,
+            col_c,
+            selector,
+            instance,
+            constant,
+        }
+    }
+
+    // Seeds F(0) = 0 and F(1) = 1 from constants and computes F(2).
+    pub fn initial_assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "first region",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                let init_a = region.assign_advice_from_constant(
+                    || "f(0)",
+                    self.config.col_a,
+                    0,
+                    F::from(0),
+                )?;
+
+                let init_b = region.assign_advice_from_constant(
+                    || "f(1)",
+                    self.config.col_b,
+                    0,
+                    F::from(1),
+                )?;
+
+                let init_c = region.assign_advice(
+                    || "f(2)",
+                    self.config.col_c,
+                    0,
+                    || init_a.value().copied() + init_b.value(),
+                )?;
+
+                Ok((init_b, init_c))
+            },
+        )
+    }
+
+    // Advances one step: (F(i-1), F(i)) -> F(i+1).
+    pub fn next_assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        prev_b: &AssignedCell<F, F>,
+        prev_c: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "next row",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                // copy the value from previous region
+                prev_b.copy_advice(|| "a", &mut region, self.config.col_a, 0)?;
+
+                prev_c.copy_advice(|| "b", &mut region, self.config.col_b, 0)?;
+
+                let res_c = region.assign_advice(
+                    || "c",
+                    self.config.col_c,
+                    0,
+                    || prev_b.value().copied() + prev_c.value(),
+                )?;
+
+                Ok(res_c)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+// `n` is the index of the exposed Fibonacci number and must be at least 2: the first row
+// already computes F(2). Smaller ones fail synthesis instead of exposing F(2).
+#[derive(Default, Clone)]
+pub struct FibonacciCircuit<F> {
+    pub n: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> FibonacciCircuit<F> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for FibonacciCircuit<F> {
+    type Config = FibonacciConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.n)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        FibonacciChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.n < 2 {
+            return Err(Error::Synthesis);
+        }
+
+        let chip = FibonacciChip::construct(config);
+
+        let (mut prev_b, mut prev_c) =
+            chip.initial_assign(layouter.namespace(|| "first region"))?;
+
+        for _i in 2..self.n {
+            let tmp_c = chip.next_assign(layouter.namespace(|| "next region"), &prev_b, &prev_c)?;
+
+            prev_b = prev_c;
+            prev_c = tmp_c;
+        }
+
+        chip.expose_public(layouter.namespace(|| "out"), &prev_c, 0)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FibonacciCircuit;
+    use halo2::{dev::MockProver, halo2curves::bn256::Fr, plonk::Error};
+
+    #[test]
+    fn fibonacci_test() {
+        let k = 4;
+
+        let output = Fr::from(34); // F(9)
+
+        let circuit = FibonacciCircuit::new(9);
+
+        let prover = MockProver::run(k, &circuit, vec![vec![output]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn fibonacci_small_n_test() {
+        let k = 4;
+
+        // F(0) = 0 and F(1) = 1 have no addition row to expose
+        for (n, output) in [(0, 0), (1, 1)] {
+            let circuit = FibonacciCircuit::new(n);
+            let result = MockProver::run(k, &circuit, vec![vec![Fr::from(output)]]);
+            assert!(matches!(result, Err(Error::Synthesis)), "n = {}", n);
+        }
+
+        // F(2) = 1 is the smallest one
+        let circuit = FibonacciCircuit::new(2);
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(1)]]).unwrap();
+        prover.assert_satisfied();
+    }
+}
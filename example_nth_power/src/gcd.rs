@@ -0,0 +1,214 @@
+// Subtractive Euclid in a bounded number of steps.
+// It proves the relation R = { ( a, b, g; n ): gcd(a, b) = g within n steps } by iterating
+// (a, b) -> (|a - b|, min(a, b)), which reaches (g, 0) and stays there. Each step witnesses
+// c = [a < b] and the gates
+//
+//   c * (1 - c)
+//   a' - ((1 - c) * (a - b) + c * (b - a))
+//   b' - ((1 - c) * b + c * a)
+//
+// hold on rows with the step selector. A wrong c makes a' negative, i.e. a huge field element,
+// which the lookup of every a and b into 0..2^VALUE_BITS rejects. The last b is pinned to 0.
+use alloc::vec;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+pub const VALUE_BITS: usize = 8;
+
+// Low 64 bits of `x`, all of it for the values the table admits. The representation is taken
+// as little-endian, as for bn256 and pasta.
+fn low_u64<F: PrimeField>(x: &F) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&x.to_repr().as_ref()[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+#[derive(Debug, Clone)]
+pub struct GcdConfig {
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub less: Column<Advice>,
+    pub step: Selector,
+    pub range: Selector,
+    pub table: TableColumn,
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
+}
+
+// Public instance: [a, b, g]. The step bound `n` is structural.
+#[derive(Default, Clone)]
+pub struct GcdCircuit<F> {
+    pub a: Value<F>,
+    pub b: Value<F>,
+    pub n: usize,
+}
+
+impl<F> GcdCircuit<F> {
+    pub fn new(a: Value<F>, b: Value<F>, n: usize) -> Self {
+        Self { a, b, n }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for GcdCircuit<F> {
+    type Config = GcdConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(Value::unknown(), Value::unknown(), self.n)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let less = meta.advice_column();
+        let step = meta.selector();
+        // selectors used in lookups can not be simple selectors
+        let range = meta.complex_selector();
+        let table = meta.lookup_table_column();
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate("euclid step", |meta| {
+            let s = meta.query_selector(step);
+            let a_cur = meta.query_advice(a, Rotation::cur());
+            let b_cur = meta.query_advice(b, Rotation::cur());
+            let a_next = meta.query_advice(a, Rotation::next());
+            let b_next = meta.query_advice(b, Rotation::next());
+            let c = meta.query_advice(less, Rotation::cur());
+
+            let not_c = Expression::Constant(F::ONE) - c.clone();
+            let diff = a_cur.clone() - b_cur.clone();
+
+            vec![
+                s.clone() * c.clone() * not_c.clone(),
+                s.clone() * (a_next - (not_c.clone() * diff.clone() - c.clone() * diff)),
+                s * (b_next - (not_c * b_cur + c * a_cur)),
+            ]
+        });
+
+        // on disabled rows the looked up value is 0, which the table contains
+        meta.lookup("a range", |meta| {
+            let s = meta.query_selector(range);
+            let a = meta.query_advice(a, Rotation::cur());
+            vec![(s * a, table)]
+        });
+        meta.lookup("b range", |meta| {
+            let s = meta.query_selector(range);
+            let b = meta.query_advice(b, Rotation::cur());
+            vec![(s * b, table)]
+        });
+
+        GcdConfig {
+            a,
+            b,
+            less,
+            step,
+            range,
+            table,
+            instance,
+            constant,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_table(
+            || "value table",
+            |mut table| {
+                for i in 0..1 << VALUE_BITS {
+                    table.assign_cell(
+                        || "value",
+                        config.table,
+                        i,
+                        || Value::known(F::from(i as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )?;
+
+        let (g, last_b) = layouter.assign_region(
+            || "euclid",
+            |mut region| {
+                let mut a =
+                    region.assign_advice_from_instance(|| "a", config.instance, 0, config.a, 0)?;
+                let mut b =
+                    region.assign_advice_from_instance(|| "b", config.instance, 1, config.b, 0)?;
+
+                for row in 0..self.n {
+                    config.step.enable(&mut region, row)?;
+                    config.range.enable(&mut region, row)?;
+
+                    let less = a
+                        .value()
+                        .zip(b.value())
+                        .map(|(a, b)| low_u64(a) < low_u64(b));
+                    region.assign_advice(
+                        || "a < b",
+                        config.less,
+                        row,
+                        || less.map(|c| F::from(c as u64)),
+                    )?;
+
+                    let next = a.value().zip(b.value()).zip(less).map(|((&a, &b), less)| {
+                        if less {
+                            (b - a, a)
+                        } else {
+                            (a - b, b)
+                        }
+                    });
+                    let (a_next, b_next) = next.unzip();
+                    a = region.assign_advice(|| "a", config.a, row + 1, || a_next)?;
+                    b = region.assign_advice(|| "b", config.b, row + 1, || b_next)?;
+                }
+                config.range.enable(&mut region, self.n)?;
+
+                Ok((a, b))
+            },
+        )?;
+
+        layouter.assign_region(
+            || "done",
+            |mut region| region.constrain_constant(last_b.cell(), F::ZERO),
+        )?;
+        layouter.constrain_instance(g.cell(), config.instance, 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GcdCircuit;
+    use halo2::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr};
+
+    fn verifies(a: u64, b: u64, g: u64, n: usize) -> bool {
+        let circuit = GcdCircuit::new(Value::known(Fr::from(a)), Value::known(Fr::from(b)), n);
+        let public_input = vec![Fr::from(a), Fr::from(b), Fr::from(g)];
+        let prover = MockProver::run(9, &circuit, vec![public_input]).unwrap();
+        prover.verify().is_ok()
+    }
+
+    #[test]
+    fn gcd_test() {
+        // (12, 8) -> (4, 8) -> (4, 4) -> (0, 4) -> (4, 0)
+        assert!(verifies(12, 8, 4, 4));
+        // once at (4, 0) further steps change nothing
+        assert!(verifies(12, 8, 4, 6));
+        assert!(verifies(8, 12, 4, 5));
+        assert!(!verifies(12, 8, 2, 6));
+    }
+
+    #[test]
+    fn gcd_bound_too_small_test() {
+        // (0, 4) after 3 steps, the last b is not 0 yet
+        assert!(!verifies(12, 8, 4, 3));
+    }
+}
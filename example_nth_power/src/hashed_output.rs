@@ -0,0 +1,135 @@
+// Power chain with a hidden output.
+// It proves the relation R = { ( h; x, y, r ): x^exp = y, Poseidon(y, r) = h }, so the verifier
+// learns a commitment to the result instead of y itself. The base is private, as in
+// example2::TestCircuitPrivateBase: with a public x anyone could recompute y from the structural
+// exp. The salt r keeps a small y from being found by hashing every candidate. The output cell
+// of the chain is hashed in circuit by the Poseidon gadget of halo2_gadgets, whose P128Pow5T3
+// parameters are defined over the pasta fields, hence Fp here. The sponge state lives in the
+// three advice columns of the chain.
+use crate::example2::{ChainMode, PowerByNumChip, PowerByNumConfig};
+use halo2::{circuit::*, halo2curves::pasta::Fp, plonk::*};
+use halo2_gadgets::poseidon::{
+    primitives::{self as poseidon, ConstantLength, P128Pow5T3},
+    Hash, Pow5Chip, Pow5Config,
+};
+
+const WIDTH: usize = 3;
+const RATE: usize = 2;
+
+// The public commitment to `y` under `salt`, as computed by the circuit.
+pub fn output_commitment(y: Fp, salt: Fp) -> Fp {
+    poseidon::Hash::<_, P128Pow5T3, ConstantLength<2>, WIDTH, RATE>::init().hash([y, salt])
+}
+
+#[derive(Debug, Clone)]
+pub struct HashedPowerConfig {
+    pub power: PowerByNumConfig,
+    pub poseidon: Pow5Config<Fp, WIDTH, RATE>,
+}
+
+// Public instance: [Poseidon(x^exp, salt)]. `exp` is structural and must be at least 1.
+#[derive(Default, Clone)]
+pub struct HashedPowerCircuit {
+    pub base: Value<Fp>,
+    pub salt: Value<Fp>,
+    pub exp: usize,
+}
+
+impl HashedPowerCircuit {
+    pub fn new(base: Value<Fp>, salt: Value<Fp>, exp: usize) -> Self {
+        Self { base, salt, exp }
+    }
+}
+
+impl Circuit<Fp> for HashedPowerCircuit {
+    type Config = HashedPowerConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(Value::unknown(), Value::unknown(), self.exp)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let power = PowerByNumChip::configure(meta);
+
+        let partial_sbox = meta.advice_column();
+        let rc_a = [(); WIDTH].map(|_| meta.fixed_column());
+        let rc_b = [(); WIDTH].map(|_| meta.fixed_column());
+        let poseidon = Pow5Chip::configure::<P128Pow5T3>(
+            meta,
+            [power.col_a, power.col_b, power.col_c],
+            partial_sbox,
+            rc_a,
+            rc_b,
+        );
+
+        HashedPowerConfig { power, poseidon }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let chip = PowerByNumChip::construct(config.power.clone());
+
+        let (_, prev_b, mut prev_c) =
+            chip.private_initial_assign(layouter.namespace(|| "first region"), self.base)?;
+
+        for i in 1..self.exp {
+            prev_c = chip.subsequent_assign(
+                layouter.namespace(|| "subsequent region"),
+                i,
+                ChainMode::MultiplyByBase,
+                &prev_b,
+                &prev_c,
+            )?;
+        }
+
+        let salt = layouter.assign_region(
+            || "salt",
+            |mut region| region.assign_advice(|| "salt", config.power.col_a, 0, || self.salt),
+        )?;
+
+        // y stays private, only its salted hash goes to the instance
+        let hasher = Hash::<_, _, P128Pow5T3, ConstantLength<2>, WIDTH, RATE>::init(
+            Pow5Chip::construct(config.poseidon),
+            layouter.namespace(|| "init hash"),
+        )?;
+        let digest = hasher.hash(layouter.namespace(|| "hash y"), [prev_c, salt])?;
+
+        layouter.constrain_instance(digest.cell(), config.power.instance, 0)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{output_commitment, HashedPowerCircuit};
+    use halo2::{circuit::Value, dev::MockProver, halo2curves::pasta::Fp};
+
+    #[test]
+    fn hashed_output_test() {
+        let k = 7;
+        let salt = Fp::from(0x5eed);
+        let circuit = HashedPowerCircuit::new(Value::known(Fp::from(2)), Value::known(salt), 3);
+
+        // 2^3 = 8, published as Poseidon(8, salt) only
+        let public_input = vec![output_commitment(Fp::from(8), salt)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // the hash of another output or under another salt, or the raw output, is rejected
+        for public_input in [
+            output_commitment(Fp::from(9), salt),
+            output_commitment(Fp::from(8), salt + Fp::from(1)),
+            Fp::from(8),
+        ] {
+            let prover = MockProver::run(k, &circuit, vec![vec![public_input]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+}
@@ -0,0 +1,241 @@
+// Boolean equality of the example2 output with a public target.
+// Instead of constraining y to the instance, it witnesses eq = 1 if y = t and 0 otherwise, with
+// d = y - t, the gates s * (d * d_inv - (1 - eq)) and s * (d * eq). For d != 0 the second forces
+// eq = 0 and the first d_inv = 1 / d; for d = 0 the first forces eq = 1.
+use crate::example2::{ChainMode, PowerByNumChip, PowerByNumConfig};
+use alloc::vec;
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+pub struct IsEqualConfig {
+    pub lhs: Column<Advice>,
+    pub rhs: Column<Advice>,
+    pub eq: Column<Advice>,
+    pub inverse: Column<Advice>,
+    pub selector: Selector,
+}
+
+#[derive(Debug, Clone)]
+pub struct IsEqualChip<F: PrimeField> {
+    config: IsEqualConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> IsEqualChip<F> {
+    pub fn construct(config: IsEqualConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    // `lhs`, `rhs` and `eq` are shared with the caller, only the inverse gets a column of its own.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        lhs: Column<Advice>,
+        rhs: Column<Advice>,
+        eq: Column<Advice>,
+    ) -> IsEqualConfig {
+        let inverse = meta.advice_column();
+        let selector = meta.selector();
+
+        meta.enable_equality(lhs);
+        meta.enable_equality(rhs);
+        meta.enable_equality(eq);
+
+        meta.create_gate("is_equal", |meta| {
+            let s = meta.query_selector(selector);
+            let lhs = meta.query_advice(lhs, Rotation::cur());
+            let rhs = meta.query_advice(rhs, Rotation::cur());
+            let eq = meta.query_advice(eq, Rotation::cur());
+            let inverse = meta.query_advice(inverse, Rotation::cur());
+
+            let diff = lhs - rhs;
+            let one = Expression::Constant(F::ONE);
+            vec![
+                s.clone() * (diff.clone() * inverse - (one - eq.clone())),
+                s * (diff * eq),
+            ]
+        });
+
+        IsEqualConfig {
+            lhs,
+            rhs,
+            eq,
+            inverse,
+            selector,
+        }
+    }
+
+    // Returns a cell holding 1 if `cell` equals row `row` of `instance`, and 0 otherwise.
+    pub fn is_equal(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        instance: Column<Instance>,
+        row: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "is_equal",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                cell.copy_advice(|| "y", &mut region, self.config.lhs, 0)?;
+                let target = region.assign_advice_from_instance(
+                    || "t",
+                    instance,
+                    row,
+                    self.config.rhs,
+                    0,
+                )?;
+
+                let diff = cell.value().copied() - target.value();
+                region.assign_advice(
+                    || "d_inv",
+                    self.config.inverse,
+                    0,
+                    || diff.map(|d| d.invert().unwrap_or(F::ZERO)),
+                )?;
+                region.assign_advice(
+                    || "eq",
+                    self.config.eq,
+                    0,
+                    || {
+                        diff.map(|d| {
+                            if bool::from(d.is_zero()) {
+                                F::ONE
+                            } else {
+                                F::ZERO
+                            }
+                        })
+                    },
+                )
+            },
+        )
+    }
+}
+
+// Public instance: [x, t, eq], with eq = 1 if x^exp = t and 0 otherwise.
+#[derive(Default, Clone)]
+pub struct IsEqualPowerCircuit<F> {
+    pub exp: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> IsEqualPowerCircuit<F> {
+    pub fn new(exp: usize) -> Self {
+        Self {
+            exp,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for IsEqualPowerCircuit<F> {
+    type Config = (PowerByNumConfig, IsEqualConfig);
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.exp)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let config = PowerByNumChip::configure(meta);
+        let is_equal_config =
+            IsEqualChip::configure(meta, config.col_a, config.col_b, config.col_c);
+        (config, is_equal_config)
+    }
+
+    fn synthesize(
+        &self,
+        (config, is_equal_config): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let instance = config.instance;
+        let chip = PowerByNumChip::construct(config);
+        let is_equal_chip = IsEqualChip::construct(is_equal_config);
+
+        // as in TestCircuit, exp = 0 lays out no multiplication row and yields the constant 1
+        let output = if self.exp == 0 {
+            chip.assign_one(layouter.namespace(|| "exp zero"))?
+        } else {
+            let (_, prev_b, mut prev_c) =
+                chip.intial_assign(layouter.namespace(|| "first region"))?;
+            for i in 1..self.exp {
+                prev_c = chip.subsequent_assign(
+                    layouter.namespace(|| "subsequent region"),
+                    i,
+                    ChainMode::MultiplyByBase,
+                    &prev_b,
+                    &prev_c,
+                )?;
+            }
+            prev_c
+        };
+
+        let eq = is_equal_chip.is_equal(layouter.namespace(|| "y == t"), &output, instance, 1)?;
+        chip.expose_public(layouter.namespace(|| "eq"), &eq, 2)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IsEqualPowerCircuit;
+    use halo2::{dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn is_equal_test() {
+        let k = 4;
+        let circuit = IsEqualPowerCircuit::new(3);
+
+        // 2^3 = 8
+        let public_input = vec![Fr::from(2), Fr::from(8), Fr::from(1)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // claiming 8 != 8 fails
+        let public_input = vec![Fr::from(2), Fr::from(8), Fr::from(0)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn is_equal_exp_zero_test() {
+        let k = 4;
+        let circuit = IsEqualPowerCircuit::new(0);
+
+        // 2^0 = 1, so t = 1 is equal and t = x is not
+        let public_input = vec![Fr::from(2), Fr::from(1), Fr::from(1)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        let public_input = vec![Fr::from(2), Fr::from(2), Fr::from(0)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        let public_input = vec![Fr::from(2), Fr::from(2), Fr::from(1)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn is_not_equal_test() {
+        let k = 4;
+        let circuit = IsEqualPowerCircuit::new(3);
+
+        // 2^3 != 9, and the proof still goes through with eq = 0
+        let public_input = vec![Fr::from(2), Fr::from(9), Fr::from(0)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // claiming 8 = 9 fails
+        let public_input = vec![Fr::from(2), Fr::from(9), Fr::from(1)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,83 @@
+// Public inputs as JSON, for verifiers outside of Rust.
+// The instance vector, e.g. [base, output] or [output], becomes an array of decimal strings
+// such as `["2", "8"]`. Strings rather than numbers, since most elements do not fit the
+// 53 bits a JSON number keeps in practice.
+use crate::parse::{field_from_str, field_to_decimal, ParseError};
+use core::fmt;
+use halo2::halo2curves::ff::PrimeField;
+
+#[derive(Debug)]
+pub enum JsonError {
+    Json(serde_json::Error),
+    // the element at `index` is not a field element
+    Element { index: usize, source: ParseError },
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::Json(source) => write!(f, "{source}"),
+            JsonError::Element { index, source } => write!(f, "element {index}: {source}"),
+        }
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+impl From<serde_json::Error> for JsonError {
+    fn from(source: serde_json::Error) -> Self {
+        JsonError::Json(source)
+    }
+}
+
+pub fn instances_to_json<F: PrimeField>(instances: &[F]) -> String {
+    let decimals: Vec<_> = instances.iter().map(field_to_decimal).collect();
+    serde_json::to_string(&decimals).expect("a list of strings always serializes")
+}
+
+pub fn instances_from_json<F: PrimeField>(json: &str) -> Result<Vec<F>, JsonError> {
+    let decimals: Vec<String> = serde_json::from_str(json)?;
+    decimals
+        .iter()
+        .enumerate()
+        .map(|(index, decimal)| {
+            field_from_str(decimal).map_err(|source| JsonError::Element { index, source })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{instances_from_json, instances_to_json, JsonError};
+    use crate::parse::ParseError;
+    use halo2::halo2curves::{bn256::Fr, ff::Field};
+
+    #[test]
+    fn json_round_trip_test() {
+        let instances = vec![Fr::from(2), Fr::from(8)];
+        let json = instances_to_json(&instances);
+        assert_eq!(json, r#"["2","8"]"#);
+        assert_eq!(instances_from_json::<Fr>(&json).unwrap(), instances);
+
+        // values beyond u64, up to -1
+        let large = vec![Fr::from(u64::MAX) + Fr::ONE, -Fr::ONE];
+        let json = instances_to_json(&large);
+        assert!(json.contains("18446744073709551616"));
+        assert_eq!(instances_from_json::<Fr>(&json).unwrap(), large);
+    }
+
+    #[test]
+    fn json_rejection_test() {
+        assert!(matches!(
+            instances_from_json::<Fr>("[2, 8]"),
+            Err(JsonError::Json(_))
+        ));
+        assert!(matches!(
+            instances_from_json::<Fr>(r#"["2", "0x"]"#),
+            Err(JsonError::Element {
+                index: 1,
+                source: ParseError::Empty
+            })
+        ));
+    }
+}
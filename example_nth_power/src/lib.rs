@@ -1,2 +1,58 @@
+// The circuits only need `core` and `alloc`, so the crate builds as `no_std` without the
+// default `std` feature. Saving keys and proofs to disk needs it. This only covers the code of
+// this crate: halo2_proofs and rand with its default features link std anyway, so the result
+// does not run on a target without std. CI checks the build with
+// `cargo check --lib --no-default-features`.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod add;
+pub mod base_range;
+pub mod bounded_power;
+pub mod constant_base;
+pub mod digest;
+pub mod disjunction;
+pub mod dry_run;
+pub mod end_to_end;
+pub mod error;
+pub mod even_power;
 pub mod example1;
 pub mod example2;
+pub mod example_ipa;
+pub mod expression;
+pub mod fibonacci;
+pub mod gcd;
+#[cfg(feature = "poseidon")]
+pub mod hashed_output;
+pub mod is_equal;
+#[cfg(feature = "json")]
+pub mod json;
+mod logging;
+#[cfg(feature = "mem-profile")]
+pub mod mem_profile;
+pub mod mod_power;
+pub mod montgomery_ladder;
+pub mod mul;
+pub mod nonzero;
+pub mod pack;
+pub mod parse;
+pub mod perfect_power;
+#[cfg(feature = "std")]
+pub mod persistence;
+#[cfg(feature = "dev-graph")]
+pub mod plot;
+pub mod poly_eval;
+pub mod power_table;
+pub mod proof_size;
+pub mod prove;
+pub mod range_check;
+pub mod square_multiply;
+pub mod step_count;
+pub mod sum_powers;
+#[cfg(feature = "std")]
+pub mod test_params;
+pub mod vdf;
+pub mod verify;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,111 @@
+// Logging of the proving stages through the `log` crate.
+// With the `log` feature every stage emits a debug record when it starts and an info record
+// with its duration when it ends. Without it `stage` only calls the closure, so the helpers pay
+// nothing for it.
+
+// Runs the stage `name` of the prover or verifier.
+#[cfg(feature = "log")]
+pub(crate) fn stage<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    log::debug!("{name} started");
+    let start = std::time::Instant::now();
+    let result = f();
+    log::info!("{name} done in {:?}", start.elapsed());
+    result
+}
+
+#[cfg(not(feature = "log"))]
+pub(crate) fn stage<T>(_: &str, f: impl FnOnce() -> T) -> T {
+    f()
+}
+
+#[cfg(all(test, feature = "log"))]
+mod tests {
+    use crate::example2::{compute_output, min_k};
+    use crate::prove::{keygen_power, prove_batch};
+    use crate::verify::verify_power_proof;
+    use halo2::{
+        halo2curves::bn256::{Bn256, Fr},
+        poly::kzg::commitment::ParamsKZG,
+    };
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+    use rand::rngs::OsRng;
+    use std::{
+        sync::{Mutex, Once},
+        thread::{self, ThreadId},
+    };
+
+    // Keeps every record, tagged with the thread that emitted it since tests run in parallel.
+    struct CapturingLogger {
+        records: Mutex<Vec<(ThreadId, Level, String)>>,
+    }
+
+    impl Log for CapturingLogger {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            let entry = (
+                thread::current().id(),
+                record.level(),
+                record.args().to_string(),
+            );
+            self.records.lock().unwrap().push(entry);
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CapturingLogger = CapturingLogger {
+        records: Mutex::new(Vec::new()),
+    };
+
+    // Records of the current thread so far.
+    fn captured() -> Vec<(Level, String)> {
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            log::set_logger(&LOGGER).unwrap();
+            log::set_max_level(LevelFilter::Debug);
+        });
+
+        let id = thread::current().id();
+        LOGGER
+            .records
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(thread, _, _)| *thread == id)
+            .map(|(_, level, message)| (*level, message.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn stage_logging_test() {
+        // installs the logger before anything is logged
+        assert!(captured().is_empty());
+
+        let exp = 3;
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), OsRng);
+        let pk = keygen_power(&params, exp).expect("keygen failed");
+
+        let base = Fr::from(2);
+        let result = compute_output(base, exp);
+        let proof = prove_batch(&params, &pk, &[(base, result, exp)], OsRng).expect("proof failed");
+        assert!(verify_power_proof(&params, pk.get_vk(), &[base, result], &proof).unwrap());
+
+        let records = captured();
+        let expected = [
+            (Level::Debug, "keygen started"),
+            (Level::Info, "keygen done in "),
+            (Level::Debug, "prove started"),
+            (Level::Info, "prove done in "),
+            (Level::Debug, "verify started"),
+            (Level::Info, "verify done in "),
+        ];
+        assert_eq!(records.len(), expected.len(), "{:?}", records);
+        for ((level, message), (expected_level, prefix)) in records.iter().zip(expected) {
+            assert_eq!(*level, expected_level);
+            assert!(message.starts_with(prefix), "{message:?} is not {prefix:?}");
+        }
+    }
+}
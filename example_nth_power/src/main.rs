@@ -0,0 +1,191 @@
+// Command line tool wrapping the KZG flow of the example2 power circuit.
+//
+//   power setup  --exp <n> [--k <k>] --params <file> --pk <file> --vk <file>
+//   power prove  --base <x> --exp <n> --params <file> --pk <file> --proof <file>
+//   power verify --base <x> --exp <n> --params <file> --vk <file> --proof <file>
+//   power verify --statement <x^n=y> --params <file> --vk <file> --proof <file>
+//   power explain --base <x> --exp <n>
+//   power mem-profile --exp <n> --k <max k>         (with the `mem-profile` feature)
+//
+// Exit codes: 0 on success, 1 when the proof does not verify, 2 on usage or IO errors.
+use example::example2::{compute_output, explain_power, min_k, TestCircuit};
+use example::parse::{fr_from_str, statement_from_str, Statement};
+use example::persistence::{
+    load_params, load_pk, load_proof, load_vk, save_params, save_pk, save_proof, save_vk,
+};
+use example::prove::prove_batch;
+use example::verify::verify_power_proof;
+use halo2::{
+    halo2curves::bn256::{Bn256, Fr},
+    plonk::*,
+    poly::kzg::commitment::ParamsKZG,
+};
+use rand::rngs::OsRng;
+use std::{collections::HashMap, env, process::ExitCode};
+
+#[cfg(feature = "mem-profile")]
+#[global_allocator]
+static ALLOCATOR: example::mem_profile::PeakAlloc = example::mem_profile::PeakAlloc;
+
+const USAGE: &str = "usage:
+  power setup  --exp <n> [--k <k>] --params <file> --pk <file> --vk <file>
+  power prove  --base <x> --exp <n> --params <file> --pk <file> --proof <file>
+  power verify --base <x> --exp <n> --params <file> --vk <file> --proof <file>
+  power verify --statement <x^n=y> --params <file> --vk <file> --proof <file>
+  power explain --base <x> --exp <n>";
+
+struct Args {
+    command: String,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut args = env::args().skip(1);
+        let command = args.next().ok_or("missing subcommand")?;
+
+        let mut options = HashMap::new();
+        while let Some(flag) = args.next() {
+            let name = flag
+                .strip_prefix("--")
+                .ok_or_else(|| format!("unexpected argument `{}`", flag))?;
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for `{}`", flag))?;
+            options.insert(name.to_owned(), value);
+        }
+
+        Ok(Self { command, options })
+    }
+
+    fn get(&self, name: &str) -> Result<&str, String> {
+        self.options
+            .get(name)
+            .map(String::as_str)
+            .ok_or_else(|| format!("missing `--{}`", name))
+    }
+
+    fn parse_num<T: std::str::FromStr>(&self, name: &str) -> Result<T, String> {
+        self.get(name)?
+            .parse()
+            .map_err(|_| format!("invalid value for `--{}`", name))
+    }
+
+    // decimal or 0x-prefixed hex, anywhere below the modulus
+    fn parse_fr(&self, name: &str) -> Result<Fr, String> {
+        fr_from_str(self.get(name)?).map_err(|e| format!("invalid value for `--{}`: {}", name, e))
+    }
+}
+
+fn setup(args: &Args) -> Result<(), String> {
+    let exp: usize = args.parse_num("exp")?;
+    let k = if args.options.contains_key("k") {
+        args.parse_num("k")?
+    } else {
+        min_k(exp)
+    };
+
+    let params = ParamsKZG::<Bn256>::setup(k, OsRng);
+    let circuit = TestCircuit::<Fr>::new(exp);
+    let vk = keygen_vk(&params, &circuit).map_err(|e| format!("keygen_vk failed: {:?}", e))?;
+    let pk = keygen_pk(&params, vk, &circuit).map_err(|e| format!("keygen_pk failed: {:?}", e))?;
+
+    save_params(args.get("params")?, &params)
+        .map_err(|e| format!("failed to write params: {}", e))?;
+    save_pk(args.get("pk")?, &pk).map_err(|e| format!("failed to write pk: {}", e))?;
+    save_vk(args.get("vk")?, pk.get_vk()).map_err(|e| format!("failed to write vk: {}", e))
+}
+
+fn prove(args: &Args) -> Result<(), String> {
+    let base = args.parse_fr("base")?;
+    let exp: usize = args.parse_num("exp")?;
+
+    let params =
+        load_params(args.get("params")?).map_err(|e| format!("failed to read params: {}", e))?;
+    let pk = load_pk::<TestCircuit<Fr>>(args.get("pk")?)
+        .map_err(|e| format!("failed to read pk: {}", e))?;
+
+    let proof = prove_batch(
+        &params,
+        &pk,
+        &[(base, compute_output(base, exp), exp)],
+        OsRng,
+    )
+    .map_err(|e| format!("proof generation failed: {:?}", e))?;
+
+    save_proof(args.get("proof")?, &proof).map_err(|e| format!("failed to write proof: {}", e))
+}
+
+fn verify(args: &Args) -> Result<bool, String> {
+    // either the whole claim, or the base and exponent with the output computed here
+    let statement = if args.options.contains_key("statement") {
+        statement_from_str(args.get("statement")?)
+            .map_err(|e| format!("invalid value for `--statement`: {}", e))?
+    } else {
+        let base = args.parse_fr("base")?;
+        let exp: usize = args.parse_num("exp")?;
+        Statement {
+            base,
+            exp,
+            output: compute_output(base, exp),
+        }
+    };
+    if !statement.holds() {
+        return Ok(false);
+    }
+
+    let params =
+        load_params(args.get("params")?).map_err(|e| format!("failed to read params: {}", e))?;
+    let vk = load_vk::<TestCircuit<Fr>>(args.get("vk")?)
+        .map_err(|e| format!("failed to read vk: {}", e))?;
+    let proof =
+        load_proof(args.get("proof")?).map_err(|e| format!("failed to read proof: {}", e))?;
+
+    let public_input = statement.instances().remove(0);
+
+    // a malformed proof is rejected like an invalid one
+    Ok(verify_power_proof(&params, &vk, &public_input, &proof).unwrap_or(false))
+}
+
+// Prints the rows the circuit assigns for base^exp.
+fn explain(args: &Args) -> Result<(), String> {
+    let base = args.parse_fr("base")?;
+    let exp: usize = args.parse_num("exp")?;
+
+    println!("{}", explain_power(base, exp));
+    Ok(())
+}
+
+// Prints the proving peak for every k from min_k(exp) up to --k.
+#[cfg(feature = "mem-profile")]
+fn mem_profile(args: &Args) -> Result<(), String> {
+    let exp: usize = args.parse_num("exp")?;
+    let max_k: u32 = args.parse_num("k")?;
+
+    example::mem_profile::print_proving_peaks(min_k(exp)..=max_k, exp);
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let result = Args::parse().and_then(|args| match args.command.as_str() {
+        "setup" => setup(&args).map(|_| true),
+        "prove" => prove(&args).map(|_| true),
+        "verify" => verify(&args),
+        "explain" => explain(&args).map(|_| true),
+        #[cfg(feature = "mem-profile")]
+        "mem-profile" => mem_profile(&args).map(|_| true),
+        other => Err(format!("unknown subcommand `{}`", other)),
+    });
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => {
+            eprintln!("proof rejected");
+            ExitCode::from(1)
+        }
+        Err(msg) => {
+            eprintln!("error: {}\n{}", msg, USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
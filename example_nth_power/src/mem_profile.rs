@@ -0,0 +1,162 @@
+// Peak memory of `create_proof` for the example2 power circuit.
+// `PeakAlloc` forwards to the system allocator and keeps the number of live bytes and its
+// maximum. The library does not install it: the binary that wants the figures does, with
+// `#[global_allocator]`, as the `power` CLI and the tests below do. The counters are process
+// wide, so allocations of other threads show up too: run alone (e.g. `--test-threads=1`) for
+// exact figures, as the tests below do by measuring in a child process.
+use crate::example2::{compute_output, TestCircuit};
+use crate::prove::prove_batch;
+use halo2::{
+    halo2curves::bn256::{Bn256, Fr},
+    plonk::{keygen_pk, keygen_vk},
+    poly::kzg::commitment::ParamsKZG,
+};
+use rand::rngs::OsRng;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+pub struct PeakAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+impl PeakAlloc {
+    fn grow(size: usize) {
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn shrink(size: usize) {
+        CURRENT.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::shrink(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            if new_size > layout.size() {
+                Self::grow(new_size - layout.size());
+            } else {
+                Self::shrink(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+// Runs `f`, returning its result and the peak number of bytes allocated on top of what was
+// live when it started.
+pub fn measure_peak<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+
+    let result = f();
+
+    let peak = PEAK.load(Ordering::Relaxed).saturating_sub(baseline);
+    (result, peak)
+}
+
+// Peak bytes allocated while proving base^exp with params of size 2^k. The setup and keys are
+// made beforehand and are not counted.
+pub fn proving_peak(k: u32, exp: usize) -> usize {
+    let params = ParamsKZG::<Bn256>::setup(k, OsRng);
+    let circuit = TestCircuit::<Fr>::new(exp);
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+
+    let base = Fr::from(2);
+    let inputs = [(base, compute_output(base, exp), exp)];
+    let (proof, peak) = measure_peak(|| prove_batch(&params, &pk, &inputs, OsRng));
+    proof.expect("proof generation failed");
+    peak
+}
+
+// Prints the proving peak for every k, each of them large enough for `exp`.
+pub fn print_proving_peaks(ks: impl IntoIterator<Item = u32>, exp: usize) {
+    for k in ks {
+        println!("k = {:2}: {} bytes", k, proving_peak(k, exp));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{proving_peak, PeakAlloc};
+    use std::{env, process::Command};
+
+    #[global_allocator]
+    static ALLOCATOR: PeakAlloc = PeakAlloc;
+
+    const CHILD_K: &str = "MEM_PROFILE_CHILD_K";
+
+    // Measures `proving_peak(k, 3)` in a child process running only `measure_in_child`, so the
+    // counters see none of the allocations of the tests running alongside in this one.
+    fn isolated_peak(k: u32) -> usize {
+        let output = Command::new(env::current_exe().expect("Failed to locate the test binary"))
+            .args([
+                "--exact",
+                "mem_profile::tests::measure_in_child",
+                "--nocapture",
+                "--test-threads=1",
+            ])
+            .env(CHILD_K, k.to_string())
+            .output()
+            .expect("Failed to run the child test");
+        assert!(output.status.success(), "child test failed for k = {}", k);
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout
+            .lines()
+            .find_map(|line| line.strip_prefix("peak = "))
+            .and_then(|peak| peak.trim().parse().ok())
+            .unwrap_or_else(|| panic!("no peak in the child output:\n{}", stdout))
+    }
+
+    // Only measures when run by `isolated_peak`, and passes trivially otherwise.
+    #[test]
+    fn measure_in_child() {
+        if let Ok(k) = env::var(CHILD_K) {
+            let k = k.parse().expect("invalid k");
+            println!("peak = {}", proving_peak(k, 3));
+        }
+    }
+
+    #[test]
+    fn proving_peak_test() {
+        assert!(proving_peak(4, 3) > 0);
+    }
+
+    #[test]
+    fn proving_peak_grows_with_k_test() {
+        let peaks: Vec<_> = [4, 7, 10].into_iter().map(isolated_peak).collect();
+
+        assert!(
+            peaks.windows(2).all(|pair| pair[0] < pair[1]),
+            "peaks not increasing in k: {:?}",
+            peaks
+        );
+    }
+}
@@ -0,0 +1,233 @@
+// Modular exponentiation with a modulus different from the field's.
+// The power chips in this crate compute x^exp in the native field, i.e. modulo the field prime.
+// This example proves the relation R = { ( x, y; exp): x^exp mod m = y } for a small m by
+// decomposing every product as a * b = q * m + r, with q and r looked up in a table of 0..m.
+// Since a, b < m we also have q < m, so the lookups pin down the integer quotient and remainder.
+use alloc::vec;
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+pub struct ModPowerConfig {
+    pub col_a: Column<Advice>,
+    pub col_b: Column<Advice>,
+    pub col_q: Column<Advice>,
+    pub col_r: Column<Advice>,
+    pub modulus: Column<Fixed>,
+    pub selector: Selector,
+    pub table: TableColumn,
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModPowerChip<F: PrimeField> {
+    config: ModPowerConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> ModPowerChip<F> {
+    pub fn construct(config: ModPowerConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> ModPowerConfig {
+        let col_a = meta.advice_column();
+        let col_b = meta.advice_column();
+        let col_q = meta.advice_column();
+        let col_r = meta.advice_column();
+        let modulus = meta.fixed_column();
+        let selector = meta.complex_selector();
+        let table = meta.lookup_table_column();
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        meta.enable_equality(col_a);
+        meta.enable_equality(col_b);
+        meta.enable_equality(col_r);
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate("mul mod", |meta| {
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let q = meta.query_advice(col_q, Rotation::cur());
+            let r = meta.query_advice(col_r, Rotation::cur());
+            let m = meta.query_fixed(modulus, Rotation::cur());
+            vec![s * (a * b - (q * m + r))]
+        });
+
+        // b is the base on every row, so it is range checked as well
+        for (name, column) in [("base < m", col_b), ("q < m", col_q), ("r < m", col_r)] {
+            meta.lookup(name, |meta| {
+                let s = meta.query_selector(selector);
+                let value = meta.query_advice(column, Rotation::cur());
+                vec![(s * value, table)]
+            });
+        }
+
+        ModPowerConfig {
+            col_a,
+            col_b,
+            col_q,
+            col_r,
+            modulus,
+            selector,
+            table,
+            instance,
+            constant,
+        }
+    }
+
+    pub fn load_table(&self, mut layouter: impl Layouter<F>, m: u64) -> Result<(), Error> {
+        layouter.assign_table(
+            || "residues",
+            |mut table| {
+                for i in 0..m {
+                    table.assign_cell(
+                        || "residue",
+                        self.config.table,
+                        i as usize,
+                        || Value::known(F::from(i)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    // Lays out exp rows, one per modular multiplication, and returns the base and result cells.
+    pub fn assign_chain(
+        &self,
+        mut layouter: impl Layouter<F>,
+        base: Value<u64>,
+        exp: usize,
+        m: u64,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "mod power",
+            |mut region| {
+                let mut acc = Value::known(1u64);
+                let mut acc_cell =
+                    region.assign_advice_from_constant(|| "one", self.config.col_a, 0, F::ONE)?;
+                let base_cell =
+                    region.assign_advice(|| "base", self.config.col_b, 0, || base.map(F::from))?;
+
+                for i in 0..exp {
+                    self.config.selector.enable(&mut region, i)?;
+
+                    if i > 0 {
+                        acc_cell =
+                            acc_cell.copy_advice(|| "a", &mut region, self.config.col_a, i)?;
+                        base_cell.copy_advice(|| "b", &mut region, self.config.col_b, i)?;
+                    }
+                    region.assign_fixed(
+                        || "m",
+                        self.config.modulus,
+                        i,
+                        || Value::known(F::from(m)),
+                    )?;
+
+                    let product = acc.zip(base).map(|(a, b)| a as u128 * b as u128);
+                    let q = product.map(|p| (p / m as u128) as u64);
+                    let r = product.map(|p| (p % m as u128) as u64);
+
+                    region.assign_advice(|| "q", self.config.col_q, i, || q.map(F::from))?;
+                    acc_cell =
+                        region.assign_advice(|| "r", self.config.col_r, i, || r.map(F::from))?;
+                    acc = r;
+                }
+
+                Ok((base_cell, acc_cell))
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+// Public instance: [x, y]. The base is witnessed as an integer so that quotients and remainders
+// can be computed natively, and tied to instance row 0. `exp` must be at least 1.
+#[derive(Default, Clone)]
+pub struct ModPowerCircuit<F> {
+    pub base: Value<u64>,
+    pub exp: usize,
+    pub modulus: u64,
+    _marker: PhantomData<F>,
+}
+
+impl<F> ModPowerCircuit<F> {
+    pub fn new(base: Value<u64>, exp: usize, modulus: u64) -> Self {
+        Self {
+            base,
+            exp,
+            modulus,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for ModPowerCircuit<F> {
+    type Config = ModPowerConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(Value::unknown(), self.exp, self.modulus)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        ModPowerChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = ModPowerChip::construct(config);
+
+        chip.load_table(layouter.namespace(|| "residues"), self.modulus)?;
+
+        let (base, result) = chip.assign_chain(
+            layouter.namespace(|| "mod power"),
+            self.base,
+            self.exp,
+            self.modulus,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "base"), &base, 0)?;
+        chip.expose_public(layouter.namespace(|| "out"), &result, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ModPowerCircuit;
+    use halo2::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn mod_power_test() {
+        let k = 4;
+
+        // 3^4 = 81 = 16 * 5 + 1
+        let circuit = ModPowerCircuit::new(Value::known(3), 4, 5);
+
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(3), Fr::from(1)]]).unwrap();
+        prover.assert_satisfied();
+    }
+}
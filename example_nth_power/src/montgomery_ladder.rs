@@ -0,0 +1,245 @@
+// Montgomery ladder exponentiation.
+// It proves the relation R = { ( x, y, exp; ): x^exp = y } like square_multiply, but every bit
+// costs the same: two cells (R0, R1) = (x^e, x^(e+1)) are carried along and both are updated
+// on every row, whatever the bit,
+//
+//   b = 0: (R0, R1) <- (R0^2, R0 * R1)
+//   b = 1: (R0, R1) <- (R0 * R1, R1^2)
+//
+// so that R1 = R0 * x throughout. The exponent is decomposed most significant bit first and
+// recomposed into `e` as in square_multiply, over one region of num_bits + 1 rows.
+use alloc::vec;
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+pub struct MontgomeryLadderConfig {
+    pub col_r0: Column<Advice>,
+    pub col_r1: Column<Advice>,
+    pub col_bit: Column<Advice>,
+    pub col_exp: Column<Advice>,
+    pub selector: Selector,
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MontgomeryLadderChip<F: PrimeField> {
+    config: MontgomeryLadderConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> MontgomeryLadderChip<F> {
+    pub fn construct(config: MontgomeryLadderConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> MontgomeryLadderConfig {
+        let col_r0 = meta.advice_column();
+        let col_r1 = meta.advice_column();
+        let col_bit = meta.advice_column();
+        let col_exp = meta.advice_column();
+        let selector = meta.selector();
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        meta.enable_equality(col_r0);
+        meta.enable_equality(col_r1);
+        meta.enable_equality(col_exp);
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate("montgomery ladder", |meta| {
+            let s = meta.query_selector(selector);
+            let r0 = meta.query_advice(col_r0, Rotation::cur());
+            let r1 = meta.query_advice(col_r1, Rotation::cur());
+            let r0_next = meta.query_advice(col_r0, Rotation::next());
+            let r1_next = meta.query_advice(col_r1, Rotation::next());
+            let bit = meta.query_advice(col_bit, Rotation::cur());
+            let exp = meta.query_advice(col_exp, Rotation::cur());
+            let exp_next = meta.query_advice(col_exp, Rotation::next());
+
+            let one = Expression::Constant(F::ONE);
+            let not_bit = one - bit.clone();
+            let product = r0.clone() * r1.clone();
+
+            vec![
+                s.clone() * bit.clone() * not_bit.clone(),
+                s.clone()
+                    * (r0_next - not_bit.clone() * r0.clone() * r0 - bit.clone() * product.clone()),
+                s.clone() * (r1_next - not_bit * product - bit.clone() * r1.clone() * r1),
+                s * (exp_next - exp * F::from(2) - bit),
+            ]
+        });
+
+        MontgomeryLadderConfig {
+            col_r0,
+            col_r1,
+            col_bit,
+            col_exp,
+            selector,
+            instance,
+            constant,
+        }
+    }
+
+    // Lays out the ladder for `exp` with the base taken from instance row 0.
+    // Returns the cells holding the result R0 and the recomposed exponent.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        exp: Value<u64>,
+        num_bits: usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        layouter.assign_region(
+            || "montgomery ladder",
+            |mut region| {
+                let mut r0 =
+                    region.assign_advice_from_constant(|| "r0", self.config.col_r0, 0, F::ONE)?;
+                let mut r1 = region.assign_advice_from_instance(
+                    || "r1",
+                    self.config.instance,
+                    0,
+                    self.config.col_r1,
+                    0,
+                )?;
+                let mut exp_acc =
+                    region.assign_advice_from_constant(|| "e", self.config.col_exp, 0, F::ZERO)?;
+
+                for i in 0..num_bits {
+                    self.config.selector.enable(&mut region, i)?;
+
+                    let bit = exp.map(|e| (e >> (num_bits - 1 - i)) & 1 == 1);
+                    region.assign_advice(
+                        || "bit",
+                        self.config.col_bit,
+                        i,
+                        || bit.map(|b| F::from(b as u64)),
+                    )?;
+
+                    let next = r0
+                        .value()
+                        .zip(r1.value())
+                        .zip(bit)
+                        .map(|((&r0, &r1), bit)| {
+                            if bit {
+                                (r0 * r1, r1.square())
+                            } else {
+                                (r0.square(), r0 * r1)
+                            }
+                        });
+                    let (r0_next, r1_next) = next.unzip();
+                    r0 = region.assign_advice(|| "r0", self.config.col_r0, i + 1, || r0_next)?;
+                    r1 = region.assign_advice(|| "r1", self.config.col_r1, i + 1, || r1_next)?;
+
+                    let exp_next = exp_acc
+                        .value()
+                        .zip(bit)
+                        .map(|(e, bit)| e.double() + F::from(bit as u64));
+                    exp_acc =
+                        region.assign_advice(|| "e", self.config.col_exp, i + 1, || exp_next)?;
+                }
+
+                Ok((r0, exp_acc))
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+// Public instance: [x, y, exp]. `num_bits` is structural and bounds exp below 2^num_bits.
+#[derive(Default, Clone)]
+pub struct MontgomeryLadderCircuit<F> {
+    pub exp: Value<u64>,
+    pub num_bits: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> MontgomeryLadderCircuit<F> {
+    pub fn new(exp: Value<u64>, num_bits: usize) -> Self {
+        Self {
+            exp,
+            num_bits,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for MontgomeryLadderCircuit<F> {
+    type Config = MontgomeryLadderConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(Value::unknown(), self.num_bits)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        MontgomeryLadderChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = MontgomeryLadderChip::construct(config);
+
+        let (result, exp) = chip.assign(
+            layouter.namespace(|| "montgomery ladder"),
+            self.exp,
+            self.num_bits,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "out"), &result, 1)?;
+        chip.expose_public(layouter.namespace(|| "exp"), &exp, 2)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MontgomeryLadderCircuit;
+    use crate::example2::{compute_output, min_k, TestCircuit};
+    use halo2::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn montgomery_ladder_test() {
+        let k = 4;
+        let num_bits = 8;
+        let input = Fr::from(3);
+
+        // the ladder agrees with the naive chain on x^exp
+        for exp in [0, 1, 2, 3, 5, 8, 13, 255] {
+            let output = compute_output(input, exp);
+
+            let naive = TestCircuit::new(exp);
+            let prover = MockProver::run(min_k(exp), &naive, vec![vec![input, output]]).unwrap();
+            prover.assert_satisfied();
+
+            let circuit = MontgomeryLadderCircuit::new(Value::known(exp as u64), num_bits);
+            let public_input = vec![input, output, Fr::from(exp as u64)];
+            let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // 3^5 is not 3^4
+        let circuit = MontgomeryLadderCircuit::new(Value::known(5), num_bits);
+        let public_input = vec![input, compute_output(input, 4), Fr::from(5)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,198 @@
+// Reusable multiplication chip shared by the example circuits.
+// It owns the gate s * (a * b - c) over three advice columns and nothing else,
+// so it carries no "power" semantics: callers decide what to feed into it.
+use alloc::{string::String, vec};
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+// `enable_equality`, skipped for columns that already have it, e.g. columns shared by the
+// gates of several chips.
+pub(crate) fn enable_equality_once<F: PrimeField>(
+    meta: &mut ConstraintSystem<F>,
+    column: impl Into<Column<Any>>,
+) {
+    let column = column.into();
+    if !meta.permutation().get_columns().contains(&column) {
+        meta.enable_equality(column);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MulConfig {
+    pub col_a: Column<Advice>,
+    pub col_b: Column<Advice>,
+    pub col_c: Column<Advice>,
+    pub selector: Selector,
+}
+
+#[derive(Debug, Clone)]
+pub struct MulChip<F: PrimeField> {
+    config: MulConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> MulChip<F> {
+    pub fn construct(config: MulConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    // The columns are allocated by the caller so that they can be shared with other gates.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        col_a: Column<Advice>,
+        col_b: Column<Advice>,
+        col_c: Column<Advice>,
+    ) -> MulConfig {
+        let selector = meta.selector();
+
+        enable_equality_once(meta, col_a);
+        enable_equality_once(meta, col_b);
+        enable_equality_once(meta, col_c);
+
+        meta.create_gate("mul", |meta| {
+            let s = meta.query_selector(selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let b = meta.query_advice(col_b, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            vec![s * (a * b - c)]
+        });
+
+        MulConfig {
+            col_a,
+            col_b,
+            col_c,
+            selector,
+        }
+    }
+
+    // Assign fresh operands and return the product cell.
+    pub fn assign_mul(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Value<F>,
+        b: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "mul",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                region.assign_advice(|| "a", self.config.col_a, 0, || a)?;
+                region.assign_advice(|| "b", self.config.col_b, 0, || b)?;
+
+                region.assign_advice(|| "a * b", self.config.col_c, 0, || a * b)
+            },
+        )
+    }
+
+    // Same as `assign_mul` but the operands are copy-constrained to already assigned cells,
+    // which is what chains of multiplications need.
+    pub fn mul_cells(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.mul_cells_named(layouter, || "mul", a, b)
+    }
+
+    // `mul_cells` in a region called `name`, which is how MockProver failures refer to it.
+    pub fn mul_cells_named<NR: Into<String>>(
+        &self,
+        layouter: impl Layouter<F>,
+        name: impl Fn() -> NR,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let product = a.value().copied() * b.value();
+        self.mul_cells_with_product(layouter, name, a, b, product)
+    }
+
+    // `mul_cells_named` with the product witnessed by the caller, e.g. read from a cache, instead
+    // of computed. The gate rejects it unless it is a * b.
+    pub fn mul_cells_with_product<NR: Into<String>>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        name: impl Fn() -> NR,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        product: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(name, |mut region| {
+            self.config.selector.enable(&mut region, 0)?;
+
+            a.copy_advice(|| "a", &mut region, self.config.col_a, 0)?;
+            b.copy_advice(|| "b", &mut region, self.config.col_b, 0)?;
+
+            region.assign_advice(|| "a * b", self.config.col_c, 0, || product)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MulChip, MulConfig};
+    use halo2::{
+        circuit::*,
+        dev::MockProver,
+        halo2curves::{bn256::Fr, ff::Field},
+        plonk::*,
+    };
+    use rand::rngs::OsRng;
+
+    #[derive(Default)]
+    struct MulCircuit {
+        a: Value<Fr>,
+        b: Value<Fr>,
+    }
+
+    impl Circuit<Fr> for MulCircuit {
+        type Config = (MulConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+        #[cfg(feature = "circuit-params")]
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let col_a = meta.advice_column();
+            let col_b = meta.advice_column();
+            let col_c = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+
+            (MulChip::configure(meta, col_a, col_b, col_c), instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let chip = MulChip::construct(config);
+            let c = chip.assign_mul(layouter.namespace(|| "a * b"), self.a, self.b)?;
+            layouter.constrain_instance(c.cell(), instance, 0)
+        }
+    }
+
+    #[test]
+    fn mul_test() {
+        let k = 3;
+
+        let a = Fr::random(OsRng);
+        let b = Fr::random(OsRng);
+
+        let circuit = MulCircuit {
+            a: Value::known(a),
+            b: Value::known(b),
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![a * b]]).unwrap();
+        prover.assert_satisfied();
+    }
+}
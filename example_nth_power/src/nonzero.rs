@@ -0,0 +1,182 @@
+// Proof that the output of the example2 power circuit is nonzero.
+// The prover witnesses y_inv next to a copy of y and the gate s * (y * y_inv - 1) holds only
+// if y has an inverse, i.e. y != 0. For y = 0 no witness satisfies it, so the prover puts 0.
+use crate::example2::{ChainMode, PowerByNumChip, PowerByNumConfig};
+use alloc::vec;
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+pub struct NonZeroConfig {
+    pub value: Column<Advice>,
+    pub inverse: Column<Advice>,
+    pub selector: Selector,
+}
+
+#[derive(Debug, Clone)]
+pub struct NonZeroChip<F: PrimeField> {
+    config: NonZeroConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> NonZeroChip<F> {
+    pub fn construct(config: NonZeroConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    // `value` is shared with the caller, only the inverse gets a column of its own.
+    pub fn configure(meta: &mut ConstraintSystem<F>, value: Column<Advice>) -> NonZeroConfig {
+        let inverse = meta.advice_column();
+        let selector = meta.selector();
+
+        meta.enable_equality(value);
+
+        meta.create_gate("nonzero", |meta| {
+            let s = meta.query_selector(selector);
+            let value = meta.query_advice(value, Rotation::cur());
+            let inverse = meta.query_advice(inverse, Rotation::cur());
+            vec![s * (value * inverse - Expression::Constant(F::ONE))]
+        });
+
+        NonZeroConfig {
+            value,
+            inverse,
+            selector,
+        }
+    }
+
+    pub fn assert_nonzero(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "nonzero",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                cell.copy_advice(|| "y", &mut region, self.config.value, 0)?;
+                region.assign_advice(
+                    || "y_inv",
+                    self.config.inverse,
+                    0,
+                    || cell.value().map(|y| y.invert().unwrap_or(F::ZERO)),
+                )?;
+                Ok(())
+            },
+        )
+    }
+}
+
+// Same statement as example2::TestCircuit, plus y != 0 when `check_nonzero` is set.
+// The flag only enables the gate, the column is allocated either way.
+#[derive(Default, Clone)]
+pub struct NonZeroPowerCircuit<F> {
+    pub exp: usize,
+    pub check_nonzero: bool,
+    _marker: PhantomData<F>,
+}
+
+impl<F> NonZeroPowerCircuit<F> {
+    pub fn new(exp: usize, check_nonzero: bool) -> Self {
+        Self {
+            exp,
+            check_nonzero,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for NonZeroPowerCircuit<F> {
+    type Config = (PowerByNumConfig, NonZeroConfig);
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.exp, self.check_nonzero)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let config = PowerByNumChip::configure(meta);
+        let nonzero_config = NonZeroChip::configure(meta, config.col_a);
+        (config, nonzero_config)
+    }
+
+    fn synthesize(
+        &self,
+        (config, nonzero_config): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PowerByNumChip::construct(config);
+        let nonzero_chip = NonZeroChip::construct(nonzero_config);
+
+        // as in TestCircuit, exp = 0 lays out no multiplication row and yields the constant 1
+        let output = if self.exp == 0 {
+            chip.assign_one(layouter.namespace(|| "exp zero"))?
+        } else {
+            let (_, prev_b, mut prev_c) =
+                chip.intial_assign(layouter.namespace(|| "first region"))?;
+            for i in 1..self.exp {
+                prev_c = chip.subsequent_assign(
+                    layouter.namespace(|| "subsequent region"),
+                    i,
+                    ChainMode::MultiplyByBase,
+                    &prev_b,
+                    &prev_c,
+                )?;
+            }
+            prev_c
+        };
+
+        if self.check_nonzero {
+            nonzero_chip.assert_nonzero(layouter.namespace(|| "nonzero"), &output)?;
+        }
+
+        chip.expose_public(layouter.namespace(|| "out"), &output, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NonZeroPowerCircuit;
+    use halo2::{dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn nonzero_test() {
+        let k = 4;
+
+        // 2^3 = 8 is nonzero
+        let circuit = NonZeroPowerCircuit::new(3, true);
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(2), Fr::from(8)]]).unwrap();
+        prover.assert_satisfied();
+
+        // 0^3 = 0 holds as a power, but not with the check on
+        let public_input = vec![Fr::from(0), Fr::from(0)];
+        let circuit = NonZeroPowerCircuit::new(3, false);
+        let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        let circuit = NonZeroPowerCircuit::new(3, true);
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn nonzero_exp_zero_test() {
+        let k = 4;
+        let circuit = NonZeroPowerCircuit::new(0, true);
+
+        // 0^0 = 1 is nonzero, and the output is not the base
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(0), Fr::from(1)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(2), Fr::from(2)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
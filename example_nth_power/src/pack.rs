@@ -0,0 +1,196 @@
+// Packing of several outputs into one public value with Horner's method.
+// It proves the relation R = { ( r, p; x_i, exp_i): p = y_1 r^(n-1) + ... + y_(n-1) r + y_n
+// where y_i = x_i^exp_i }, so the verifier gets a single instance row for all n outputs
+// instead of one each. A "pack" gate s * (acc * r + y - acc_next) on four advice columns adds
+// one output per row. As long as every output is below r, p is their base-r representation
+// and `pack_instances` recomputes it off-circuit.
+use crate::example2::{PowerByNumChip, PowerByNumConfig};
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+pub struct PackConfig {
+    pub power: PowerByNumConfig,
+    pub acc_next: Column<Advice>,
+    pub selector: Selector,
+}
+
+#[derive(Debug, Clone)]
+pub struct PackChip<F: PrimeField> {
+    config: PackConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> PackChip<F> {
+    pub fn construct(config: PackConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    // acc, r and y sit on the power chip's a, b and c columns, acc_next on a new one.
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> PackConfig {
+        let power = PowerByNumChip::configure(meta);
+        let acc_next = meta.advice_column();
+        let selector = meta.selector();
+
+        meta.enable_equality(acc_next);
+
+        meta.create_gate("pack", |meta| {
+            let s = meta.query_selector(selector);
+            let acc = meta.query_advice(power.col_a, Rotation::cur());
+            let r = meta.query_advice(power.col_b, Rotation::cur());
+            let y = meta.query_advice(power.col_c, Rotation::cur());
+            let acc_next = meta.query_advice(acc_next, Rotation::cur());
+            vec![s * (acc * r + y - acc_next)]
+        });
+
+        PackConfig {
+            power,
+            acc_next,
+            selector,
+        }
+    }
+
+    pub fn power_chip(&self) -> PowerByNumChip<F> {
+        PowerByNumChip::construct(self.config.power.clone())
+    }
+
+    pub fn load_r(
+        &self,
+        mut layouter: impl Layouter<F>,
+        row: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "r",
+            |mut region| {
+                region.assign_advice_from_instance(
+                    || "r",
+                    self.config.power.instance,
+                    row,
+                    self.config.power.col_b,
+                    0,
+                )
+            },
+        )
+    }
+
+    // Folds `values`, first one most significant, into a single cell. Needs at least one.
+    pub fn pack(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[AssignedCell<F, F>],
+        r: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let (first, rest) = values.split_first().expect("nothing to pack");
+
+        let mut acc = first.clone();
+        for y in rest {
+            acc = layouter.assign_region(
+                || "pack",
+                |mut region| {
+                    self.config.selector.enable(&mut region, 0)?;
+
+                    acc.copy_advice(|| "acc", &mut region, self.config.power.col_a, 0)?;
+                    r.copy_advice(|| "r", &mut region, self.config.power.col_b, 0)?;
+                    y.copy_advice(|| "y", &mut region, self.config.power.col_c, 0)?;
+
+                    region.assign_advice(
+                        || "acc * r + y",
+                        self.config.acc_next,
+                        0,
+                        || acc.value().copied() * r.value() + y.value(),
+                    )
+                },
+            )?;
+        }
+        Ok(acc)
+    }
+}
+
+// The packed value a verifier expects, by the same Horner steps as the gate.
+pub fn pack_instances<F: PrimeField>(values: &[F], r: F) -> F {
+    values.iter().fold(F::ZERO, |acc, &y| acc * r + y)
+}
+
+// Public instance: [r, p]. Proves the powers x_i^exp_i of private bases and exposes only
+// their packing p.
+#[derive(Default, Clone)]
+pub struct PackedPowersCircuit<F> {
+    pub bases: Vec<Value<F>>,
+    pub exps: Vec<usize>,
+}
+
+impl<F> PackedPowersCircuit<F> {
+    pub fn new(bases: Vec<Value<F>>, exps: Vec<usize>) -> Self {
+        Self { bases, exps }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for PackedPowersCircuit<F> {
+    type Config = PackConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(vec![Value::unknown(); self.bases.len()], self.exps.clone())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        PackChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PackChip::construct(config);
+        let power_chip = chip.power_chip();
+
+        let powers = power_chip.assign_power_vector(
+            layouter.namespace(|| "powers"),
+            &self.bases,
+            &self.exps,
+        )?;
+
+        let r = chip.load_r(layouter.namespace(|| "r"), 0)?;
+        let packed = chip.pack(layouter.namespace(|| "pack"), &powers, &r)?;
+        power_chip.expose_public(layouter.namespace(|| "out"), &packed, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pack_instances, PackedPowersCircuit};
+    use crate::example2::min_k;
+    use halo2::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn pack_test() {
+        let bases = [2u64, 3, 5]
+            .map(|base| Value::known(Fr::from(base)))
+            .to_vec();
+        let circuit = PackedPowersCircuit::new(bases, vec![3, 2, 2]);
+        // the power chains, then r and two pack rows
+        let k = min_k(3 + 2 + 2 + 3);
+
+        // [8, 9, 25] in base 1000
+        let r = Fr::from(1000);
+        let packed = pack_instances(&[8u64, 9, 25].map(Fr::from), r);
+        assert_eq!(packed, Fr::from(8_009_025));
+
+        let prover = MockProver::run(k, &circuit, vec![vec![r, packed]]).unwrap();
+        prover.assert_satisfied();
+
+        // outputs in another order pack differently
+        let swapped = pack_instances(&[9u64, 8, 25].map(Fr::from), r);
+        let prover = MockProver::run(k, &circuit, vec![vec![r, swapped]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,220 @@
+// Parsing of field elements from user input.
+// `Fr::from(u64)` cannot express values above 2^64, so the CLI and tests read elements as
+// decimal or `0x`-prefixed hex strings instead. Values at or above the modulus are rejected,
+// not reduced. `field_to_decimal` goes the other way.
+// `statement_from_str` reads a whole claim `base^exp=output`, as given to `power verify`.
+use crate::example2::{compute_output, instances};
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+use halo2::halo2curves::{bn256::Fr, ff::PrimeField, pasta::Fp};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    InvalidDigit(char),
+    OutOfRange,
+    Statement,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "no digits"),
+            ParseError::InvalidDigit(c) => write!(f, "invalid digit `{c}`"),
+            ParseError::OutOfRange => write!(f, "value is not below the field modulus"),
+            ParseError::Statement => write!(f, "expected a statement `base^exp=output`"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+// Parses into any field whose representation is little-endian, as for bn256 and pasta.
+pub fn field_from_str<F: PrimeField>(s: &str) -> Result<F, ParseError> {
+    let (digits, radix) = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => (hex, 16),
+        None => (s, 10),
+    };
+    if digits.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    // repr = repr * radix + digit, carried byte by byte
+    let mut repr = F::Repr::default();
+    for c in digits.chars() {
+        let mut carry = c.to_digit(radix).ok_or(ParseError::InvalidDigit(c))?;
+        for byte in repr.as_mut().iter_mut() {
+            let value = *byte as u32 * radix + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return Err(ParseError::OutOfRange);
+        }
+    }
+
+    Option::from(F::from_repr(repr)).ok_or(ParseError::OutOfRange)
+}
+
+// Canonical decimal form of `x`, which `field_from_str` reads back.
+pub fn field_to_decimal<F: PrimeField>(x: &F) -> String {
+    let mut repr = x.to_repr();
+
+    // repr = repr / 10 from the most significant byte, keeping the remainders as digits
+    let mut digits = Vec::new();
+    loop {
+        let mut remainder = 0u32;
+        for byte in repr.as_mut().iter_mut().rev() {
+            let value = (remainder << 8) | *byte as u32;
+            *byte = (value / 10) as u8;
+            remainder = value % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+        if repr.as_ref().iter().all(|&byte| byte == 0) {
+            break;
+        }
+    }
+
+    digits.iter().rev().map(|&digit| digit as char).collect()
+}
+
+// The claim base^exp = output. Only its base and output are public: the exponent is fixed by
+// the verifying key, so a statement that does not hold is rejected before the proof is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Statement<F> {
+    pub base: F,
+    pub exp: usize,
+    pub output: F,
+}
+
+impl<F: PrimeField> Statement<F> {
+    pub fn holds(&self) -> bool {
+        compute_output(self.base, self.exp) == self.output
+    }
+
+    pub fn instances(&self) -> Vec<Vec<F>> {
+        instances(self.base, self.output)
+    }
+}
+
+// Reads `base^exp=output`, with base and output as in `field_from_str` and a decimal exponent.
+pub fn statement_from_str<F: PrimeField>(s: &str) -> Result<Statement<F>, ParseError> {
+    let (base, rest) = s.split_once('^').ok_or(ParseError::Statement)?;
+    let (exp, output) = rest.split_once('=').ok_or(ParseError::Statement)?;
+
+    Ok(Statement {
+        base: field_from_str(base.trim())?,
+        exp: exp_from_str(exp.trim())?,
+        output: field_from_str(output.trim())?,
+    })
+}
+
+fn exp_from_str(s: &str) -> Result<usize, ParseError> {
+    if s.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    s.chars().try_fold(0usize, |exp, c| {
+        let digit = c.to_digit(10).ok_or(ParseError::InvalidDigit(c))?;
+        exp.checked_mul(10)
+            .and_then(|exp| exp.checked_add(digit as usize))
+            .ok_or(ParseError::OutOfRange)
+    })
+}
+
+pub fn fr_from_str(s: &str) -> Result<Fr, ParseError> {
+    field_from_str(s)
+}
+
+pub fn fp_from_str(s: &str) -> Result<Fp, ParseError> {
+    field_from_str(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{field_to_decimal, fp_from_str, fr_from_str, statement_from_str, ParseError};
+    use halo2::halo2curves::{bn256::Fr, ff::Field, pasta::Fp};
+
+    #[test]
+    fn parse_decimal_test() {
+        assert_eq!(fr_from_str("4096"), Ok(Fr::from(4096)));
+        assert_eq!(fp_from_str("0"), Ok(Fp::ZERO));
+
+        // 2^64 does not fit the u64 constructor
+        let two_64 = Fr::from(u64::MAX) + Fr::ONE;
+        assert_eq!(fr_from_str("18446744073709551616"), Ok(two_64));
+    }
+
+    #[test]
+    fn parse_hex_test() {
+        assert_eq!(fr_from_str("0xff"), Ok(Fr::from(255)));
+
+        // the largest element of bn256 Fr, i.e. -1
+        let p_minus_1 = "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000";
+        assert_eq!(fr_from_str(p_minus_1), Ok(-Fr::ONE));
+    }
+
+    #[test]
+    fn parse_rejection_test() {
+        // the bn256 Fr modulus itself, and a value beyond 256 bits
+        let p = "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
+        assert_eq!(fr_from_str(p), Err(ParseError::OutOfRange));
+        assert_eq!(
+            fr_from_str(&format!("0x1{}", "0".repeat(64))),
+            Err(ParseError::OutOfRange)
+        );
+
+        assert_eq!(fr_from_str(""), Err(ParseError::Empty));
+        assert_eq!(fr_from_str("0x"), Err(ParseError::Empty));
+        assert_eq!(fr_from_str("12a"), Err(ParseError::InvalidDigit('a')));
+    }
+
+    #[test]
+    fn decimal_round_trip_test() {
+        assert_eq!(field_to_decimal(&Fr::ZERO), "0");
+        assert_eq!(field_to_decimal(&Fr::from(4096)), "4096");
+
+        let p_minus_1 = -Fr::ONE;
+        let decimal = field_to_decimal(&p_minus_1);
+        assert_eq!(
+            decimal,
+            "21888242871839275222246405745257275088548364400416034343698204186575808495616"
+        );
+        assert_eq!(fr_from_str(&decimal), Ok(p_minus_1));
+    }
+
+    #[test]
+    fn parse_statement_test() {
+        let statement = statement_from_str::<Fr>("2^3=8").unwrap();
+        assert_eq!(
+            (statement.base, statement.exp, statement.output),
+            (Fr::from(2), 3, Fr::from(8))
+        );
+        assert!(statement.holds());
+        assert_eq!(statement.instances(), vec![vec![Fr::from(2), Fr::from(8)]]);
+
+        // spaces and hex elements are accepted, and a false claim still parses
+        let statement = statement_from_str::<Fr>("0x3 ^ 2 = 10").unwrap();
+        assert_eq!(statement.exp, 2);
+        assert!(!statement.holds());
+    }
+
+    #[test]
+    fn statement_rejection_test() {
+        assert_eq!(statement_from_str::<Fr>("2^^3"), Err(ParseError::Statement));
+        assert_eq!(statement_from_str::<Fr>("2^3="), Err(ParseError::Empty));
+        assert_eq!(
+            statement_from_str::<Fr>("2*3=6"),
+            Err(ParseError::Statement)
+        );
+        assert_eq!(
+            statement_from_str::<Fr>("2^^3=8"),
+            Err(ParseError::InvalidDigit('^'))
+        );
+        assert_eq!(
+            statement_from_str::<Fr>("2^-3=8"),
+            Err(ParseError::InvalidDigit('-'))
+        );
+    }
+}
@@ -0,0 +1,171 @@
+// Perfect powers.
+// It proves the relation R = { ( y; x, exp ): x^exp = y over the integers, exp >= 2,
+// 0 <= x < 2^BOUND_BITS } with the square-and-multiply ladder run on a private base. The exponent
+// is already decomposed into bits by the ladder, and exp >= 2 holds exactly when exp >> 1, the
+// exponent recomposed from all bits but the last, is nonzero, which the nonzero chip checks.
+// Neither x nor exp is exposed.
+// In the field alone the statement would be empty: x -> x^5 is a bijection of Fr, so a fifth
+// root of any y exists. The base is therefore range checked with the running sum of
+// bounded_power, and num_bits is capped so that x^exp stays below the modulus: no power of the
+// ladder wraps, and y is the integer power.
+use crate::bounded_power::{BoundChip, BoundConfig, BOUND_BITS};
+use crate::nonzero::{NonZeroChip, NonZeroConfig};
+use crate::square_multiply::{SquareMultiplyChip, SquareMultiplyConfig};
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*};
+
+// x < 2^BOUND_BITS and exp < 2^num_bits bound every power of the ladder by
+// 2^(BOUND_BITS * (2^num_bits - 1)), which has to stay below the modulus.
+fn fits_without_wrapping<F: PrimeField>(num_bits: usize) -> bool {
+    num_bits < 8 && BOUND_BITS * ((1 << num_bits) - 1) < F::CAPACITY as usize
+}
+
+// Public instance: [y]. `num_bits` is structural and bounds exp below 2^num_bits, at most 4 for
+// Fr (exp <= 15), larger ones fail synthesis.
+#[derive(Default, Clone)]
+pub struct PerfectPowerCircuit<F> {
+    pub base: Value<F>,
+    pub exp: Value<u64>,
+    pub num_bits: usize,
+}
+
+impl<F> PerfectPowerCircuit<F> {
+    pub fn new(base: Value<F>, exp: Value<u64>, num_bits: usize) -> Self {
+        Self {
+            base,
+            exp,
+            num_bits,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for PerfectPowerCircuit<F> {
+    type Config = (SquareMultiplyConfig, NonZeroConfig, BoundConfig);
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(Value::unknown(), Value::unknown(), self.num_bits)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let config = SquareMultiplyChip::configure(meta);
+        let nonzero_config = NonZeroChip::configure(meta, config.col_exp);
+        // only the running sum rows of the bound chip are used, on the ladder's columns
+        let range_config = BoundChip::configure(
+            meta,
+            config.col_base,
+            config.col_bit,
+            config.col_exp,
+            config.instance,
+        );
+        (config, nonzero_config, range_config)
+    }
+
+    fn synthesize(
+        &self,
+        (config, nonzero_config, range_config): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if !fits_without_wrapping::<F>(self.num_bits) {
+            return Err(Error::Synthesis);
+        }
+
+        let col_base = config.col_base;
+        let chip = SquareMultiplyChip::construct(config);
+        let nonzero_chip = NonZeroChip::construct(nonzero_config);
+        let range_chip = BoundChip::construct(range_config);
+
+        // x < 2^BOUND_BITS
+        let base = layouter.assign_region(
+            || "base",
+            |mut region| region.assign_advice(|| "base", col_base, 0, || self.base),
+        )?;
+        range_chip.check_range(layouter.namespace(|| "base range"), &base)?;
+
+        let (result, prefixes) = chip.assign_copied_base(
+            layouter.namespace(|| "square and multiply"),
+            &base,
+            self.exp,
+            self.num_bits,
+        )?;
+
+        // exp >> 1 != 0, i.e. exp >= 2
+        let half_exp = &prefixes[self.num_bits - 1];
+        nonzero_chip.assert_nonzero(layouter.namespace(|| "exp >= 2"), half_exp)?;
+
+        chip.expose_public(layouter.namespace(|| "out"), &result, 0)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PerfectPowerCircuit;
+    use halo2::{
+        circuit::Value,
+        dev::MockProver,
+        halo2curves::{
+            bn256::Fr,
+            ff::{Field, PrimeField},
+        },
+        plonk::Error,
+    };
+
+    const NUM_BITS: usize = 4;
+
+    fn verifies_fr(base: Fr, exp: u64, y: u64) -> bool {
+        let circuit = PerfectPowerCircuit::new(Value::known(base), Value::known(exp), NUM_BITS);
+        let prover = MockProver::run(6, &circuit, vec![vec![Fr::from(y)]]).unwrap();
+        prover.verify().is_ok()
+    }
+
+    fn verifies(base: u64, exp: u64, y: u64) -> bool {
+        verifies_fr(Fr::from(base), exp, y)
+    }
+
+    #[test]
+    fn perfect_power_test() {
+        // 64 = 2^6 = 4^3 = 8^2, any of them will do
+        assert!(verifies(2, 6, 64));
+        assert!(verifies(4, 3, 64));
+        assert!(verifies(8, 2, 64));
+
+        // 64^1 is not a witness, the exponent must be at least 2
+        assert!(!verifies(64, 1, 64));
+    }
+
+    #[test]
+    fn prime_is_not_a_perfect_power_test() {
+        // 7 is only 7^1, and other candidates miss it
+        assert!(!verifies(7, 1, 7));
+        assert!(!verifies(2, 3, 7));
+        assert!(!verifies(7, 0, 7));
+
+        // 7 has a fifth root in Fr, 7^(5^-1 mod p - 1), which is out of the base range
+        let root = Fr::from_str_vartime(
+            "2615350521154998089307251263374356604855106075743514092799182656534496184573",
+        )
+        .unwrap();
+        assert_eq!(root.pow_vartime([5]), Fr::from(7));
+        assert!(!verifies_fr(root, 5, 7));
+    }
+
+    #[test]
+    fn base_range_test() {
+        // the largest base squares without wrapping
+        let base = (1 << 16) - 1;
+        assert!(verifies(base, 2, base * base));
+        assert!(!verifies(base + 1, 2, (base + 1) * (base + 1)));
+    }
+
+    #[test]
+    fn wide_exponent_test() {
+        // 5 bits would allow x^31 up to 2^496, past the modulus
+        let circuit = PerfectPowerCircuit::new(Value::known(Fr::from(2)), Value::known(6), 5);
+        let result = MockProver::run(6, &circuit, vec![vec![Fr::from(64)]]);
+        assert!(matches!(result, Err(Error::Synthesis)));
+    }
+}
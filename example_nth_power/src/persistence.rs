@@ -0,0 +1,547 @@
+// Saving and loading of the KZG artifacts (params, keys and proofs) used by the example2 flow.
+// Keys are stored with `SerdeFormat::RawBytes`, and every helper reports IO failures instead of
+// panicking so benches, tests and the CLI can decide what to do with them.
+// The `_tagged` variants prefix the artifact with the k and circuit version it was made for, so a
+// cache left over from another k or an older layout is detected before the payload is parsed.
+// Keys and proofs also record a digest of the params they were made with: a key from another
+// setup of the same k would deserialize fine, and only fail later as proofs that do not verify.
+// Witness tables hold the intermediate powers of a chain, to be fed back into
+// `TestCircuitCachedWitness` instead of recomputed.
+// With the `compress` feature every artifact is gzipped on save and inflated on load, which
+// mostly pays off for proving keys.
+#[cfg(feature = "compress")]
+use flate2::{bufread::GzDecoder, write::GzEncoder, Compression};
+use halo2::{
+    halo2curves::{
+        bn256::{Bn256, Fr, G1Affine},
+        ff::PrimeField,
+        group::GroupEncoding,
+    },
+    plonk::{Circuit, ProvingKey, VerifyingKey},
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+    SerdeFormat,
+};
+use rand::{rngs::OsRng, RngCore};
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+// Bump whenever the layout of the example2 circuit changes, so that keys cached for the old
+// layout are regenerated instead of read back.
+pub const CIRCUIT_VERSION: u32 = 1;
+
+const TAG_MAGIC: [u8; 4] = *b"xnp\x01";
+
+#[cfg(feature = "compress")]
+type ArtifactWriter = GzEncoder<BufWriter<File>>;
+#[cfg(not(feature = "compress"))]
+type ArtifactWriter = BufWriter<File>;
+
+#[cfg(feature = "compress")]
+type ArtifactReader = GzDecoder<BufReader<File>>;
+#[cfg(not(feature = "compress"))]
+type ArtifactReader = BufReader<File>;
+
+fn create_artifact(path: impl AsRef<Path>) -> io::Result<ArtifactWriter> {
+    let writer = BufWriter::new(File::create(path)?);
+    #[cfg(feature = "compress")]
+    let writer = GzEncoder::new(writer, Compression::default());
+    Ok(writer)
+}
+
+// Ends the gzip stream, if any, before flushing: dropping the encoder would lose its errors.
+fn finish_artifact(writer: ArtifactWriter) -> io::Result<()> {
+    #[cfg(feature = "compress")]
+    let mut writer = writer.finish()?;
+    #[cfg(not(feature = "compress"))]
+    let mut writer = writer;
+    writer.flush()
+}
+
+fn open_artifact(path: impl AsRef<Path>) -> io::Result<ArtifactReader> {
+    let reader = BufReader::new(File::open(path)?);
+    #[cfg(feature = "compress")]
+    let reader = GzDecoder::new(reader);
+    Ok(reader)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArtifactTag {
+    pub k: u32,
+    pub version: u32,
+    // `params_digest` of the params the artifact depends on, zero for the params themselves.
+    pub params: [u8; 8],
+}
+
+impl ArtifactTag {
+    pub fn new(k: u32) -> Self {
+        Self {
+            k,
+            version: CIRCUIT_VERSION,
+            params: [0; 8],
+        }
+    }
+
+    // Tag of an artifact derived from `params`, such as a key or a proof.
+    pub fn for_params(params: &ParamsKZG<Bn256>) -> Self {
+        Self {
+            params: params_digest(params),
+            ..Self::new(params.k())
+        }
+    }
+
+    fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(&TAG_MAGIC)?;
+        writer.write_all(&self.version.to_le_bytes())?;
+        writer.write_all(&self.k.to_le_bytes())?;
+        writer.write_all(&self.params)
+    }
+
+    fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let mut bytes = [0u8; 20];
+        reader.read_exact(&mut bytes)?;
+        if bytes[..4] != TAG_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "artifact has no tag",
+            ));
+        }
+        let word = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+        Ok(Self {
+            version: word(4),
+            k: word(8),
+            params: bytes[12..].try_into().unwrap(),
+        })
+    }
+
+    // Reads the tag at the start of `reader`, failing unless it is `self`.
+    fn expect(&self, reader: &mut impl Read) -> io::Result<()> {
+        let found = Self::read(reader)?;
+        if found != *self {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "artifact made for k = {} (version {}, params {:02x?}), expected k = {} \
+                     (version {}, params {:02x?})",
+                    found.k, found.version, found.params, self.k, self.version, self.params
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Identifies a setup by its [s]_2, the only point of the params a verifier needs besides the
+// generators, truncated to 8 bytes of Blake2b.
+pub fn params_digest(params: &ParamsKZG<Bn256>) -> [u8; 8] {
+    let hash = blake2b_simd::Params::new()
+        .hash_length(8)
+        .hash(params.s_g2().to_bytes().as_ref());
+
+    let mut digest = [0; 8];
+    digest.copy_from_slice(hash.as_bytes());
+    digest
+}
+
+pub fn save_proof(path: impl AsRef<Path>, proof: &[u8]) -> io::Result<()> {
+    let mut writer = create_artifact(path)?;
+    writer.write_all(proof)?;
+    finish_artifact(writer)
+}
+
+pub fn load_proof(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+    let mut proof = Vec::new();
+    open_artifact(path)?.read_to_end(&mut proof)?;
+    Ok(proof)
+}
+
+pub fn save_proof_tagged(
+    path: impl AsRef<Path>,
+    params: &ParamsKZG<Bn256>,
+    proof: &[u8],
+) -> io::Result<()> {
+    let mut writer = create_artifact(path)?;
+    ArtifactTag::for_params(params).write(&mut writer)?;
+    writer.write_all(proof)?;
+    finish_artifact(writer)
+}
+
+pub fn load_proof_tagged(path: impl AsRef<Path>, params: &ParamsKZG<Bn256>) -> io::Result<Vec<u8>> {
+    let mut reader = open_artifact(path)?;
+    ArtifactTag::for_params(params).expect(&mut reader)?;
+    let mut proof = Vec::new();
+    reader.read_to_end(&mut proof)?;
+    Ok(proof)
+}
+
+// One 32 byte representation per intermediate power, as built by `example2::witness_table`.
+pub fn save_witness_table(path: impl AsRef<Path>, powers: &[Fr]) -> io::Result<()> {
+    let mut writer = create_artifact(path)?;
+    for power in powers {
+        writer.write_all(power.to_repr().as_ref())?;
+    }
+    finish_artifact(writer)
+}
+
+pub fn load_witness_table(path: impl AsRef<Path>) -> io::Result<Vec<Fr>> {
+    let bytes = load_proof(path)?;
+    if bytes.len() % 32 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "witness table is not a whole number of elements",
+        ));
+    }
+
+    bytes
+        .chunks(32)
+        .map(|chunk| {
+            let mut repr = <Fr as PrimeField>::Repr::default();
+            repr.as_mut().copy_from_slice(chunk);
+            Option::from(Fr::from_repr(repr)).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "witness table entry out of range",
+                )
+            })
+        })
+        .collect()
+}
+
+pub fn save_params(path: impl AsRef<Path>, params: &ParamsKZG<Bn256>) -> io::Result<()> {
+    let mut writer = create_artifact(path)?;
+    params.write(&mut writer)?;
+    finish_artifact(writer)
+}
+
+pub fn load_params(path: impl AsRef<Path>) -> io::Result<ParamsKZG<Bn256>> {
+    ParamsKZG::read(&mut open_artifact(path)?)
+}
+
+pub fn save_pk(path: impl AsRef<Path>, pk: &ProvingKey<G1Affine>) -> io::Result<()> {
+    let mut writer = create_artifact(path)?;
+    pk.write(&mut writer, SerdeFormat::RawBytes)?;
+    finish_artifact(writer)
+}
+
+pub fn load_pk<C: Circuit<Fr>>(path: impl AsRef<Path>) -> io::Result<ProvingKey<G1Affine>> {
+    ProvingKey::read::<_, C>(&mut open_artifact(path)?, SerdeFormat::RawBytes)
+}
+
+pub fn save_vk(path: impl AsRef<Path>, vk: &VerifyingKey<G1Affine>) -> io::Result<()> {
+    let mut writer = create_artifact(path)?;
+    vk.write(&mut writer, SerdeFormat::RawBytes)?;
+    finish_artifact(writer)
+}
+
+pub fn load_vk<C: Circuit<Fr>>(path: impl AsRef<Path>) -> io::Result<VerifyingKey<G1Affine>> {
+    VerifyingKey::read::<_, C>(&mut open_artifact(path)?, SerdeFormat::RawBytes)
+}
+
+pub fn save_params_tagged(path: impl AsRef<Path>, params: &ParamsKZG<Bn256>) -> io::Result<()> {
+    let mut writer = create_artifact(path)?;
+    ArtifactTag::new(params.k()).write(&mut writer)?;
+    params.write(&mut writer)?;
+    finish_artifact(writer)
+}
+
+pub fn load_params_tagged(path: impl AsRef<Path>, k: u32) -> io::Result<ParamsKZG<Bn256>> {
+    let mut reader = open_artifact(path)?;
+    ArtifactTag::new(k).expect(&mut reader)?;
+    ParamsKZG::read(&mut reader)
+}
+
+// Loads the params cached at `path`, or sets up and caches fresh ones if the file is missing or
+// was made for another k or circuit version.
+pub fn load_or_setup_params(
+    path: impl AsRef<Path>,
+    k: u32,
+    rng: impl RngCore,
+) -> io::Result<ParamsKZG<Bn256>> {
+    match load_params_tagged(&path, k) {
+        Ok(params) => Ok(params),
+        Err(_) => {
+            let params = ParamsKZG::<Bn256>::setup(k, rng);
+            save_params_tagged(&path, &params)?;
+            Ok(params)
+        }
+    }
+}
+
+// Where `get_or_create_params` keeps its params: $KZG_PARAMS_DIR if set, ./benches/data of the
+// crate otherwise.
+pub fn params_dir() -> PathBuf {
+    env::var_os("KZG_PARAMS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("benches/data"))
+}
+
+// Universal params of size 2^k, set up once and shared by every bench and test that asks for
+// the same k.
+pub fn get_or_create_params(k: u32) -> ParamsKZG<Bn256> {
+    get_or_create_params_in(params_dir(), k).expect("Failed to cache params")
+}
+
+// `get_or_create_params` under `dir`. Fresh params are written to a file of their own and renamed
+// into place, so tests running in parallel never read a half written file.
+pub fn get_or_create_params_in(dir: impl AsRef<Path>, k: u32) -> io::Result<ParamsKZG<Bn256>> {
+    static WRITES: AtomicUsize = AtomicUsize::new(0);
+
+    let path = dir.as_ref().join(format!("params_kzg_k{k}"));
+    if let Ok(params) = load_params_tagged(&path, k) {
+        return Ok(params);
+    }
+
+    fs::create_dir_all(&dir)?;
+    let params = ParamsKZG::<Bn256>::setup(k, OsRng);
+    let write = WRITES.fetch_add(1, Ordering::Relaxed);
+    let partial = path.with_extension(format!("{}.{}", process::id(), write));
+    save_params_tagged(&partial, &params)?;
+    fs::rename(&partial, &path)?;
+    Ok(params)
+}
+
+pub fn save_pk_tagged(
+    path: impl AsRef<Path>,
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+) -> io::Result<()> {
+    let mut writer = create_artifact(path)?;
+    ArtifactTag::for_params(params).write(&mut writer)?;
+    pk.write(&mut writer, SerdeFormat::RawBytes)?;
+    finish_artifact(writer)
+}
+
+pub fn load_pk_tagged<C: Circuit<Fr>>(
+    path: impl AsRef<Path>,
+    params: &ParamsKZG<Bn256>,
+) -> io::Result<ProvingKey<G1Affine>> {
+    let mut reader = open_artifact(path)?;
+    ArtifactTag::for_params(params).expect(&mut reader)?;
+    ProvingKey::read::<_, C>(&mut reader, SerdeFormat::RawBytes)
+}
+
+pub fn save_vk_tagged(
+    path: impl AsRef<Path>,
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+) -> io::Result<()> {
+    let mut writer = create_artifact(path)?;
+    ArtifactTag::for_params(params).write(&mut writer)?;
+    vk.write(&mut writer, SerdeFormat::RawBytes)?;
+    finish_artifact(writer)
+}
+
+pub fn load_vk_tagged<C: Circuit<Fr>>(
+    path: impl AsRef<Path>,
+    params: &ParamsKZG<Bn256>,
+) -> io::Result<VerifyingKey<G1Affine>> {
+    let mut reader = open_artifact(path)?;
+    ArtifactTag::for_params(params).expect(&mut reader)?;
+    VerifyingKey::read::<_, C>(&mut reader, SerdeFormat::RawBytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        get_or_create_params, get_or_create_params_in, load_or_setup_params, load_params_tagged,
+        load_pk, load_proof, load_proof_tagged, load_vk, load_vk_tagged, load_witness_table,
+        save_params_tagged, save_pk, save_proof, save_proof_tagged, save_vk, save_vk_tagged,
+        save_witness_table,
+    };
+    use crate::example2::{
+        compute_output, min_k, witness_table, TestCircuit, TestCircuitCachedWitness,
+    };
+    use crate::prove::prove_batch;
+    use crate::verify::verify_power_proof;
+    use halo2::{
+        dev::MockProver,
+        halo2curves::bn256::{Bn256, Fr},
+        plonk::{keygen_pk, keygen_vk},
+        poly::{commitment::Params, kzg::commitment::ParamsKZG},
+    };
+    use rand::rngs::OsRng;
+    use std::fs;
+
+    #[test]
+    fn pk_round_trip_test() {
+        let exp = 3;
+        let params = get_or_create_params(min_k(exp));
+
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("pk");
+        save_pk(&path, &pk).expect("Failed to write pk");
+        let pk = load_pk::<TestCircuit<Fr>>(&path).expect("Failed to read pk");
+
+        // the reloaded key still proves
+        let base = Fr::from(2);
+        let inputs = [(base, compute_output(base, exp), exp)];
+        assert!(prove_batch(&params, &pk, &inputs, OsRng).is_ok());
+    }
+
+    #[test]
+    fn verify_with_vk_only_test() {
+        let exp = 3;
+        let params = get_or_create_params(min_k(exp));
+
+        let base = Fr::from(2);
+        let instances = [base, compute_output(base, exp)];
+
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let vk_path = dir.path().join("vk");
+        let proof_path = dir.path().join("proof");
+        {
+            let circuit = TestCircuit::<Fr>::new(exp);
+            let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+            let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+
+            let proof = prove_batch(&params, &pk, &[(base, instances[1], exp)], OsRng)
+                .expect("proof generation failed");
+            save_vk(&vk_path, pk.get_vk()).expect("Failed to write vk");
+            save_proof(&proof_path, &proof).expect("Failed to write proof");
+        }
+
+        // the prover side is gone, only the files are left
+        let vk = load_vk::<TestCircuit<Fr>>(&vk_path).expect("Failed to read vk");
+        let proof = load_proof(&proof_path).expect("Failed to read proof");
+
+        assert!(verify_power_proof(&params, &vk, &instances, &proof).unwrap());
+    }
+
+    #[test]
+    fn stale_params_are_regenerated_test() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("params");
+        let params = ParamsKZG::<Bn256>::setup(3, OsRng);
+        save_params_tagged(&path, &params).expect("Failed to write params");
+
+        // the k = 3 file is rejected for k = 4, then replaced
+        assert!(load_params_tagged(&path, 4).is_err());
+        let params = load_or_setup_params(&path, 4, OsRng).expect("Failed to regenerate params");
+        assert_eq!(params.k(), 4);
+        let params = load_params_tagged(&path, 4).expect("Failed to read regenerated params");
+        assert_eq!(params.k(), 4);
+    }
+
+    #[test]
+    fn cached_witness_table_test() {
+        let exp = 6;
+        let base = Fr::from(3);
+        let public_input = vec![base, compute_output(base, exp)];
+
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("witness_table");
+        save_witness_table(&path, &witness_table(base, exp)).expect("Failed to write table");
+        let mut powers = load_witness_table(&path).expect("Failed to read table");
+
+        let circuit = TestCircuitCachedWitness::new(exp, &powers);
+        let prover = MockProver::run(min_k(exp), &circuit, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        // a tampered entry breaks the mul gate of its step
+        powers[2] += Fr::from(1);
+        let circuit = TestCircuitCachedWitness::new(exp, &powers);
+        let prover = MockProver::run(min_k(exp), &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn shared_params_are_reused_test() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("params_kzg_k4");
+
+        // the first call sets up and writes the params
+        let params = get_or_create_params_in(dir.path(), 4).expect("Failed to cache params");
+        let created = fs::metadata(&path).and_then(|m| m.modified()).unwrap();
+
+        // the second reads them back without rewriting the file
+        let reloaded = get_or_create_params_in(dir.path(), 4).expect("Failed to cache params");
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).unwrap();
+        assert_eq!(created, modified);
+
+        let mut written = vec![];
+        params.write(&mut written).unwrap();
+        let mut read = vec![];
+        reloaded.write(&mut read).unwrap();
+        assert_eq!(written, read);
+
+        // other k get a file of their own, and nothing partial is left behind
+        get_or_create_params_in(dir.path(), 3).expect("Failed to cache params");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn artifacts_of_other_params_are_rejected_test() {
+        let exp = 3;
+        let k = min_k(exp);
+        let params = ParamsKZG::<Bn256>::setup(k, OsRng);
+        let other = ParamsKZG::<Bn256>::setup(k, OsRng);
+
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+        let base = Fr::from(2);
+        let proof = prove_batch(
+            &params,
+            &pk,
+            &[(base, compute_output(base, exp), exp)],
+            OsRng,
+        )
+        .expect("proof generation failed");
+
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let vk_path = dir.path().join("vk");
+        let proof_path = dir.path().join("proof");
+        save_vk_tagged(&vk_path, &params, pk.get_vk()).expect("Failed to write vk");
+        save_proof_tagged(&proof_path, &params, &proof).expect("Failed to write proof");
+
+        // same k and version, but another setup
+        assert!(load_vk_tagged::<TestCircuit<Fr>>(&vk_path, &other).is_err());
+        assert!(load_proof_tagged(&proof_path, &other).is_err());
+
+        assert!(load_vk_tagged::<TestCircuit<Fr>>(&vk_path, &params).is_ok());
+        assert_eq!(load_proof_tagged(&proof_path, &params).unwrap(), proof);
+    }
+
+    #[cfg(feature = "compress")]
+    #[test]
+    fn compressed_pk_round_trip_test() {
+        use halo2::SerdeFormat;
+
+        let exp = 3;
+        let params = get_or_create_params(min_k(exp));
+
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+        let mut raw = vec![];
+        pk.write(&mut raw, SerdeFormat::RawBytes).unwrap();
+
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("pk");
+        save_pk(&path, &pk).expect("Failed to write pk");
+        let compressed = fs::metadata(&path).expect("Failed to stat pk").len();
+        assert!(
+            compressed < raw.len() as u64,
+            "{} compressed bytes for {} raw",
+            compressed,
+            raw.len()
+        );
+
+        // the key is inflated on load and still proves
+        let pk = load_pk::<TestCircuit<Fr>>(&path).expect("Failed to read pk");
+        let base = Fr::from(2);
+        let output = compute_output(base, exp);
+        let proof = prove_batch(&params, &pk, &[(base, output, exp)], OsRng)
+            .expect("proof generation failed");
+        assert!(verify_power_proof(&params, pk.get_vk(), &[base, output], &proof).unwrap());
+    }
+}
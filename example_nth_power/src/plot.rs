@@ -0,0 +1,34 @@
+// Renders the region layout of the example2 power circuit to a PNG, so one can see the
+// regions and columns fill up as the exponent grows. Only built with the `dev-graph` feature.
+use crate::example2::{min_k, TestCircuit};
+use halo2::{dev::CircuitLayout, halo2curves::bn256::Fr};
+use plotters::prelude::*;
+use std::error::Error;
+
+// Lays out TestCircuit for `exp` on the smallest domain holding it, see `min_k`.
+pub fn plot_power_circuit(exp: usize, path: &str) -> Result<(), Box<dyn Error>> {
+    let root = BitMapBackend::new(path, (1024, 768)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let circuit = TestCircuit::<Fr>::new(exp);
+    CircuitLayout::default().render(min_k(exp), &circuit, &root)?;
+
+    root.present()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::plot_power_circuit;
+    use std::fs;
+
+    #[test]
+    fn plot_power_circuit_test() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("layout.png");
+        let path = path.to_str().unwrap();
+
+        plot_power_circuit(5, path).expect("Failed to plot the layout");
+        assert!(fs::metadata(path).unwrap().len() > 0);
+    }
+}
@@ -0,0 +1,208 @@
+// Polynomial evaluation with Horner's method.
+// It proves the relation R = { ( x, y; ): c_n x^n + ... + c_1 x + c_0 = y } for coefficients
+// fixed in the circuit, alternating the mul chip with an add-constant gate s * (a + k - c)
+// where k is read from a fixed column. Negative coefficients are plain field negations,
+// so subtraction comes for free.
+use crate::mul::{MulChip, MulConfig};
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+pub struct PolyEvalConfig {
+    pub mul: MulConfig,
+    pub coeff: Column<Fixed>,
+    pub add_selector: Selector,
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PolyEvalChip<F: PrimeField> {
+    config: PolyEvalConfig,
+    _marker: PhantomData<F>,
+}
+
+// Maps a signed integer coefficient into the field, e.g. -2 to p - 2.
+pub fn signed<F: PrimeField>(c: i64) -> F {
+    let abs = F::from(c.unsigned_abs());
+    if c < 0 {
+        -abs
+    } else {
+        abs
+    }
+}
+
+impl<F: PrimeField> PolyEvalChip<F> {
+    pub fn construct(config: PolyEvalConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> PolyEvalConfig {
+        let col_a = meta.advice_column();
+        let col_b = meta.advice_column();
+        let col_c = meta.advice_column();
+        let coeff = meta.fixed_column();
+        let add_selector = meta.selector();
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        let mul = MulChip::configure(meta, col_a, col_b, col_c);
+
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate("add constant", |meta| {
+            let s = meta.query_selector(add_selector);
+            let a = meta.query_advice(col_a, Rotation::cur());
+            let k = meta.query_fixed(coeff, Rotation::cur());
+            let c = meta.query_advice(col_c, Rotation::cur());
+            vec![s * (a + k - c)]
+        });
+
+        PolyEvalConfig {
+            mul,
+            coeff,
+            add_selector,
+            instance,
+            constant,
+        }
+    }
+
+    pub fn load_x(&self, mut layouter: impl Layouter<F>) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "x",
+            |mut region| {
+                region.assign_advice_from_instance(
+                    || "x",
+                    self.config.instance,
+                    0,
+                    self.config.mul.col_b,
+                    0,
+                )
+            },
+        )
+    }
+
+    pub fn load_constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        k: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "leading coefficient",
+            |mut region| region.assign_advice_from_constant(|| "c_n", self.config.mul.col_c, 0, k),
+        )
+    }
+
+    pub fn add_constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        k: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "add constant",
+            |mut region| {
+                self.config.add_selector.enable(&mut region, 0)?;
+
+                a.copy_advice(|| "a", &mut region, self.config.mul.col_a, 0)?;
+                region.assign_fixed(|| "k", self.config.coeff, 0, || Value::known(k))?;
+
+                region.assign_advice(
+                    || "a + k",
+                    self.config.mul.col_c,
+                    0,
+                    || a.value().copied() + Value::known(k),
+                )
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+// Public instance: [x, p(x)]. `coeffs` holds c_0, c_1, ..., c_n and is part of the circuit.
+#[derive(Default, Clone)]
+pub struct PolyEvalCircuit<F> {
+    pub coeffs: Vec<F>,
+}
+
+impl<F: PrimeField> Circuit<F> for PolyEvalCircuit<F> {
+    type Config = PolyEvalConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        PolyEvalChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PolyEvalChip::construct(config.clone());
+        let mul_chip = MulChip::construct(config.mul);
+
+        let x = chip.load_x(layouter.namespace(|| "x"))?;
+
+        // acc = c_n, then acc = acc * x + c_i down to c_0
+        let mut coeffs = self.coeffs.iter().rev();
+        let leading = coeffs.next().copied().unwrap_or(F::ZERO);
+        let mut acc = chip.load_constant(layouter.namespace(|| "c_n"), leading)?;
+
+        for &c in coeffs {
+            let product = mul_chip.mul_cells(layouter.namespace(|| "acc * x"), &acc, &x)?;
+            acc = chip.add_constant(layouter.namespace(|| "acc + c"), &product, c)?;
+        }
+
+        chip.expose_public(layouter.namespace(|| "out"), &acc, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{signed, PolyEvalCircuit};
+    use halo2::{dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn poly_eval_test() {
+        let k = 4;
+
+        // x^2 + 2x + 1 at x = 3
+        let circuit = PolyEvalCircuit {
+            coeffs: vec![Fr::from(1), Fr::from(2), Fr::from(1)],
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(3), Fr::from(16)]]).unwrap();
+        prover.assert_satisfied();
+
+        // x^2 - 2x + 1 at x = 3
+        let circuit = PolyEvalCircuit {
+            coeffs: vec![signed(1), signed(-2), signed(1)],
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(3), Fr::from(4)]]).unwrap();
+        prover.assert_satisfied();
+
+        // the value of the other polynomial, 16, is rejected
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(3), Fr::from(16)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
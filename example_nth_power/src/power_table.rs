@@ -0,0 +1,160 @@
+// Membership of y in a table of powers.
+// It proves the relation R = { ( y; ): y = x^i for some 0 <= i <= n } for a base x and a bound
+// n fixed in the circuit. Nothing is multiplied: the table x^0, x^1, ..., x^n is loaded once
+// and y, copied from the instance, is looked up in it.
+use alloc::vec;
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+pub struct PowerTableConfig {
+    pub y: Column<Advice>,
+    pub selector: Selector,
+    pub table: TableColumn,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PowerTableChip<F: PrimeField> {
+    config: PowerTableConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> PowerTableChip<F> {
+    pub fn construct(config: PowerTableConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> PowerTableConfig {
+        let y = meta.advice_column();
+        // selectors used in lookups can not be simple selectors
+        let selector = meta.complex_selector();
+        let table = meta.lookup_table_column();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(y);
+        meta.enable_equality(instance);
+
+        // the table need not hold 0, so disabled rows look up x^0 = 1 instead
+        meta.lookup("power table", |meta| {
+            let s = meta.query_selector(selector);
+            let y = meta.query_advice(y, Rotation::cur());
+            let one = Expression::Constant(F::ONE);
+            vec![(s.clone() * y + (one - s), table)]
+        });
+
+        PowerTableConfig {
+            y,
+            selector,
+            table,
+            instance,
+        }
+    }
+
+    pub fn load_table(
+        &self,
+        mut layouter: impl Layouter<F>,
+        base: F,
+        n: usize,
+    ) -> Result<(), Error> {
+        layouter.assign_table(
+            || "powers",
+            |mut table| {
+                let mut power = F::ONE;
+                for i in 0..=n {
+                    table.assign_cell(|| "x^i", self.config.table, i, || Value::known(power))?;
+                    power *= base;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    // Copies y from instance row `row` and looks it up.
+    pub fn assign_member(&self, mut layouter: impl Layouter<F>, row: usize) -> Result<(), Error> {
+        layouter.assign_region(
+            || "member",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                region.assign_advice_from_instance(
+                    || "y",
+                    self.config.instance,
+                    row,
+                    self.config.y,
+                    0,
+                )?;
+                Ok(())
+            },
+        )
+    }
+}
+
+// Public instance: [y]. The base and n are structural, and the table takes n + 1 rows.
+#[derive(Default, Clone)]
+pub struct PowerTableCircuit<F> {
+    pub base: u64,
+    pub n: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> PowerTableCircuit<F> {
+    pub fn new(base: u64, n: usize) -> Self {
+        Self {
+            base,
+            n,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for PowerTableCircuit<F> {
+    type Config = PowerTableConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.base, self.n)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        PowerTableChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PowerTableChip::construct(config);
+
+        chip.load_table(layouter.namespace(|| "table"), F::from(self.base), self.n)?;
+        chip.assign_member(layouter.namespace(|| "y"), 0)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PowerTableCircuit;
+    use halo2::{dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn power_table_test() {
+        let k = 4;
+        let circuit = PowerTableCircuit::<Fr>::new(2, 8);
+
+        // 16 = 2^4
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(16)]]).unwrap();
+        prover.assert_satisfied();
+
+        // 17 is no power of 2
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(17)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
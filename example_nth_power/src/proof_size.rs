@@ -0,0 +1,140 @@
+// Rough size of a KZG (GWC) proof, to sanity check serialized proofs.
+// With KZG the proof does not grow with k: it holds a fixed number of commitments and
+// evaluations per column, lookup and permutation chunk, all read off the constraint system.
+// A proof far from the estimate usually means it was made for another circuit.
+// `measure_proof_size` makes an actual proof instead, to compare sizes across k.
+#[cfg(feature = "std")]
+use crate::example2::{compute_output, min_k};
+#[cfg(feature = "std")]
+use crate::prove::{keygen_power, prove_batch};
+use core::ops::Range;
+#[cfg(feature = "std")]
+use halo2::{
+    halo2curves::bn256::{Bn256, Fr},
+    poly::kzg::commitment::ParamsKZG,
+};
+use halo2::{halo2curves::ff::Field, plonk::ConstraintSystem};
+#[cfg(feature = "std")]
+use rand::rngs::OsRng;
+
+// compressed bn256 G1 points and Fr scalars
+const POINT_BYTES: usize = 32;
+const SCALAR_BYTES: usize = 32;
+
+// GWC sends one witness commitment per opening point: x, ωx and the last row, plus ω^-1 x
+// for lookups. Counted as three, the estimate does not need to be exact.
+const OPENING_POINTS: usize = 3;
+
+// Estimated length in bytes of a proof for `num_circuits` instances of the circuit behind `cs`.
+pub fn expected_proof_len<F: Field>(cs: &ConstraintSystem<F>, num_circuits: usize) -> usize {
+    let degree = cs.degree();
+    let lookups = cs.lookups().len();
+
+    let permutation_columns = cs.permutation().get_columns().len();
+    let permutation_sets = (permutation_columns + degree - 3) / (degree - 2);
+
+    // advice columns, then per lookup the permuted input, permuted table and product
+    let circuit_points = cs.num_advice_columns() + 3 * lookups + permutation_sets;
+    // the random polynomial and the degree - 1 pieces of the quotient
+    let shared_points = 1 + (degree - 1) + OPENING_POINTS;
+
+    // every set is evaluated at x and ωx, and all but the last at the last row too
+    let circuit_scalars =
+        cs.advice_queries().len() + 5 * lookups + (3 * permutation_sets).saturating_sub(1);
+    let shared_scalars = cs.fixed_queries().len() + permutation_columns + 1;
+
+    (num_circuits * circuit_points + shared_points) * POINT_BYTES
+        + (num_circuits * circuit_scalars + shared_scalars) * SCALAR_BYTES
+}
+
+// Lengths within a factor of two of the estimate.
+pub fn proof_len_window<F: Field>(cs: &ConstraintSystem<F>, num_circuits: usize) -> Range<usize> {
+    let expected = expected_proof_len(cs, num_circuits);
+    expected / 2..expected * 2 + 1
+}
+
+// Length in bytes of a proof of base^exp made with params of size 2^k.
+#[cfg(feature = "std")]
+pub fn measure_proof_size(k: u32, base: u64, exp: usize) -> usize {
+    assert!(k >= min_k(exp), "x^{exp} does not fit k = {k}");
+
+    let params = ParamsKZG::<Bn256>::setup(k, OsRng);
+    let pk = keygen_power(&params, exp).expect("keygen failed");
+
+    let base = Fr::from(base);
+    let inputs = [(base, compute_output(base, exp), exp)];
+    prove_batch(&params, &pk, &inputs, OsRng)
+        .expect("proof generation failed")
+        .len()
+}
+
+// Prints the proof size of base^exp for every k, e.g. 3..=10 for an exponent fitting k = 3.
+#[cfg(feature = "std")]
+pub fn print_proof_sizes(ks: impl IntoIterator<Item = u32>, base: u64, exp: usize) {
+    println!("{:>3} | {:>6}", "k", "bytes");
+    for k in ks {
+        println!("{:>3} | {:>6}", k, measure_proof_size(k, base, exp));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{measure_proof_size, proof_len_window};
+    use crate::example2::{compute_output, min_k, TestCircuit};
+    use crate::prove::prove_batch;
+    use halo2::{
+        halo2curves::bn256::{Bn256, Fr},
+        plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem},
+        poly::kzg::commitment::ParamsKZG,
+    };
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn proof_len_test() {
+        let exp = 3;
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), OsRng);
+
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+        let cs = pk.get_vk().cs();
+
+        for num_circuits in [1, 3] {
+            let inputs: Vec<_> = (2..2 + num_circuits as u64)
+                .map(|base| (Fr::from(base), compute_output(Fr::from(base), exp), exp))
+                .collect();
+            let proof = prove_batch(&params, &pk, &inputs, OsRng).expect("proof generation failed");
+
+            let window = proof_len_window(cs, num_circuits);
+            assert!(
+                window.contains(&proof.len()),
+                "{} bytes outside of {:?}",
+                proof.len(),
+                window
+            );
+        }
+
+        // a truncated proof is flagged
+        assert!(!proof_len_window(cs, 1).contains(&64));
+    }
+
+    #[test]
+    fn measure_proof_size_test() {
+        let exp = 2;
+        let size = measure_proof_size(4, 2, exp);
+
+        let mut meta = ConstraintSystem::<Fr>::default();
+        TestCircuit::<Fr>::configure(&mut meta);
+        let window = proof_len_window(&meta, 1);
+        assert!(
+            window.contains(&size),
+            "{} bytes outside of {:?}",
+            size,
+            window
+        );
+
+        // a KZG proof does not grow with the domain
+        assert_eq!(measure_proof_size(min_k(exp), 2, exp), size);
+        assert_eq!(measure_proof_size(6, 2, exp), size);
+    }
+}
@@ -0,0 +1,562 @@
+// KZG proving helpers for the example2 power circuit.
+use crate::example2::{assert_instances_consistent, compute_output, instances, TestCircuit};
+use crate::logging::stage;
+use crate::verify::verify_power_proof;
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
+use alloc::{vec, vec::Vec};
+use halo2::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{create_proof, keygen_pk, keygen_vk, Error, ProvingKey, VerifyingKey},
+    poly::{
+        commitment::Params,
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::ProverGWC,
+        },
+    },
+    transcript::{Blake2bWrite, Challenge255, EncodedChallenge, TranscriptWriterBuffer},
+};
+#[cfg(feature = "std")]
+use rand::rngs::OsRng;
+use rand::RngCore;
+#[cfg(feature = "std")]
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+// Generates the proving key of the power circuit for `exp`.
+pub fn keygen_power(params: &ParamsKZG<Bn256>, exp: usize) -> Result<ProvingKey<G1Affine>, Error> {
+    stage("keygen", || {
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let vk = keygen_vk(params, &circuit)?;
+        keygen_pk(params, vk, &circuit)
+    })
+}
+
+// Proves many (base, result, exp) statements in a single proof, one circuit instance per triple.
+// The exponent is structural, so every triple has to use the exponent `pk` was generated for.
+// `rng` only draws the blinding factors: a seeded one gives byte-identical proofs.
+pub fn prove_batch(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    inputs: &[(Fr, Fr, usize)],
+    rng: impl RngCore,
+) -> Result<Vec<u8>, Error> {
+    prove_batch_with_transcript::<Challenge255<G1Affine>, Blake2bWrite<_, _, _>>(
+        params, pk, inputs, rng,
+    )
+}
+
+// `prove_batch` with another transcript than Blake2b, e.g. a Keccak256 one for EVM verifiers
+// or an algebraic one for recursion. The verifier has to read the proof with the same one.
+pub fn prove_batch_with_transcript<
+    E: EncodedChallenge<G1Affine>,
+    T: TranscriptWriterBuffer<Vec<u8>, G1Affine, E>,
+>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    inputs: &[(Fr, Fr, usize)],
+    rng: impl RngCore,
+) -> Result<Vec<u8>, Error> {
+    if let Some(&(_, _, exp)) = inputs.first() {
+        assert!(
+            inputs.iter().all(|&(_, _, e)| e == exp),
+            "all statements of a batch must share the same exponent"
+        );
+    }
+
+    for &(base, result, exp) in inputs {
+        assert_instances_consistent(base, exp, result);
+    }
+
+    let circuits: Vec<TestCircuit<Fr>> = inputs
+        .iter()
+        .map(|&(_, _, exp)| TestCircuit::new(exp))
+        .collect();
+
+    // one instance column per circuit, holding [base, result]
+    let columns: Vec<Vec<Vec<Fr>>> = inputs
+        .iter()
+        .map(|&(base, result, _)| instances(base, result))
+        .collect();
+    let instances: Vec<Vec<&[Fr]>> = columns
+        .iter()
+        .map(|columns| columns.iter().map(Vec::as_slice).collect())
+        .collect();
+    let instances: Vec<&[&[Fr]]> = instances.iter().map(|i| i.as_slice()).collect();
+
+    let mut transcript = T::init(vec![]);
+    stage("prove", || {
+        create_proof::<KZGCommitmentScheme<_>, ProverGWC<_>, _, _, _, _>(
+            params,
+            pk,
+            &circuits,
+            &instances,
+            rng,
+            &mut transcript,
+        )
+    })?;
+
+    Ok(transcript.finalize())
+}
+
+// A proof together with what its verifier needs besides the vk: the instances it was made
+// for and the k of the params. With the `serde` feature it is saved as one file, the
+// instances as decimal strings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerProof {
+    pub bytes: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "decimal_instances"))]
+    pub instances: Vec<Fr>,
+    pub k: u32,
+}
+
+impl PowerProof {
+    // Params of another size cannot have produced the proof, so they reject it outright.
+    pub fn verify(
+        &self,
+        params: &ParamsKZG<Bn256>,
+        vk: &VerifyingKey<G1Affine>,
+    ) -> Result<bool, Error> {
+        if params.k() != self.k {
+            return Ok(false);
+        }
+        verify_power_proof(params, vk, &self.instances, &self.bytes)
+    }
+
+    // Whether both proofs claim the same statement, i.e. the same instances at the same k.
+    // The proof bytes are not compared: blinding makes two proofs of one statement differ.
+    pub fn same_statement(&self, other: &PowerProof) -> bool {
+        self.k == other.k && self.instances == other.instances
+    }
+}
+
+#[cfg(feature = "serde")]
+mod decimal_instances {
+    use crate::parse::{field_to_decimal, fr_from_str};
+    use alloc::{string::String, vec::Vec};
+    use halo2::halo2curves::bn256::Fr;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(instances: &[Fr], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(instances.iter().map(field_to_decimal))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Fr>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|decimal| fr_from_str(decimal).map_err(D::Error::custom))
+            .collect()
+    }
+}
+
+// Proves base^exp for the exponent `pk` was generated for, bundled with its instances.
+pub fn create_power_proof(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    base: Fr,
+    exp: usize,
+    rng: impl RngCore,
+) -> Result<PowerProof, Error> {
+    let result = compute_output(base, exp);
+    let bytes = prove_batch(params, pk, &[(base, result, exp)], rng)?;
+
+    Ok(PowerProof {
+        bytes,
+        instances: vec![base, result],
+        k: params.k(),
+    })
+}
+
+// `prove_batch` for a single statement, run on tokio's blocking pool so that an async service
+// awaits the proof instead of stalling its runtime. A panic while proving is resumed here.
+#[cfg(feature = "tokio")]
+pub async fn prove_power_async(
+    params: Arc<ParamsKZG<Bn256>>,
+    pk: Arc<ProvingKey<G1Affine>>,
+    base: Fr,
+    result: Fr,
+    exp: usize,
+) -> Result<Vec<u8>, Error> {
+    let task = tokio::task::spawn_blocking(move || {
+        prove_batch(&params, &pk, &[(base, result, exp)], OsRng)
+    });
+
+    match task.await {
+        Ok(proof) => proof,
+        Err(error) => std::panic::resume_unwind(error.into_panic()),
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum ProveError {
+    // the proof took longer than the time it was given
+    Timeout,
+    // the `CancelToken` was cancelled before the proof was done
+    Cancelled,
+    Halo2(Error),
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for ProveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProveError::Timeout => write!(f, "proving timed out"),
+            ProveError::Cancelled => write!(f, "proving was cancelled"),
+            ProveError::Halo2(source) => write!(f, "proof generation failed: {source:?}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ProveError {}
+
+// Shared flag asking a proof started with `prove_cancellable` to give up. Clones share it.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+#[cfg(feature = "std")]
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// How often a waiting caller looks at its cancel token.
+#[cfg(feature = "std")]
+const CANCEL_POLL: Duration = Duration::from_millis(10);
+
+// `prove_batch` for a single statement on a thread of its own, given up on when `token` is
+// cancelled or `timeout` elapses. halo2 cannot stop `create_proof` midway, so the thread of a
+// given up proof keeps running in the background and its result is dropped.
+#[cfg(feature = "std")]
+pub fn prove_cancellable(
+    params: Arc<ParamsKZG<Bn256>>,
+    pk: Arc<ProvingKey<G1Affine>>,
+    (base, result, exp): (Fr, Fr, usize),
+    token: &CancelToken,
+    timeout: Option<Duration>,
+) -> Result<Vec<u8>, ProveError> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+    let (sender, receiver) = mpsc::channel();
+    let handle = thread::spawn(move || {
+        // the receiver is gone once the caller gave up
+        let _ = sender.send(prove_batch(&params, &pk, &[(base, result, exp)], OsRng));
+    });
+
+    loop {
+        if token.is_cancelled() {
+            return Err(ProveError::Cancelled);
+        }
+        let wait = match deadline {
+            Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                Some(left) => left.min(CANCEL_POLL),
+                None => return Err(ProveError::Timeout),
+            },
+            None => CANCEL_POLL,
+        };
+
+        match receiver.recv_timeout(wait) {
+            Ok(proof) => return proof.map_err(ProveError::Halo2),
+            Err(RecvTimeoutError::Timeout) => continue,
+            // the prover panicked before sending anything
+            Err(RecvTimeoutError::Disconnected) => match handle.join() {
+                Err(panic) => std::panic::resume_unwind(panic),
+                Ok(()) => unreachable!("the prover returned without a result"),
+            },
+        }
+    }
+}
+
+// `prove_cancellable` with only a time budget, `Err(ProveError::Timeout)` once it is spent.
+#[cfg(feature = "std")]
+pub fn prove_with_timeout(
+    params: Arc<ParamsKZG<Bn256>>,
+    pk: Arc<ProvingKey<G1Affine>>,
+    statement: (Fr, Fr, usize),
+    timeout: Duration,
+) -> Result<Vec<u8>, ProveError> {
+    prove_cancellable(
+        params,
+        pk,
+        statement,
+        &CancelToken::default(),
+        Some(timeout),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{create_power_proof, prove_batch};
+    use crate::example2::{compute_output, min_k, TestCircuit};
+    use halo2::{
+        halo2curves::bn256::{Bn256, Fr},
+        plonk::{keygen_pk, keygen_vk, verify_proof},
+        poly::{
+            commitment::ParamsProver,
+            kzg::{
+                commitment::{KZGCommitmentScheme, ParamsKZG},
+                multiopen::VerifierGWC,
+                strategy::AccumulatorStrategy,
+            },
+            VerificationStrategy,
+        },
+        transcript::{Blake2bRead, Challenge255, TranscriptReadBuffer},
+    };
+    use rand::{rngs::OsRng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn prove_batch_test() {
+        let exp = 3;
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), OsRng);
+
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+
+        let inputs: Vec<(Fr, Fr, usize)> = [2u64, 3, 5]
+            .into_iter()
+            .map(|base| {
+                let base = Fr::from(base);
+                (base, compute_output(base, exp), exp)
+            })
+            .collect();
+
+        let proof = prove_batch(&params, &pk, &inputs, OsRng).expect("proof generation failed");
+
+        // verify all three statements together
+        let instances: Vec<Vec<Fr>> = inputs.iter().map(|&(b, r, _)| vec![b, r]).collect();
+        let instances: Vec<Vec<&[Fr]>> = instances.iter().map(|i| vec![i.as_slice()]).collect();
+        let instances: Vec<&[&[Fr]]> = instances.iter().map(|i| i.as_slice()).collect();
+
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof.as_slice());
+        let strategy = verify_proof::<KZGCommitmentScheme<_>, VerifierGWC<_>, _, _, _>(
+            params.verifier_params(),
+            pk.get_vk(),
+            AccumulatorStrategy::new(params.verifier_params()),
+            &instances,
+            &mut transcript,
+        )
+        .expect("proof verification failed");
+        assert!(VerificationStrategy::<_, VerifierGWC<_>>::finalize(
+            strategy
+        ));
+    }
+
+    #[test]
+    fn prove_batch_seeded_test() {
+        let exp = 3;
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), OsRng);
+
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+
+        let base = Fr::from(2);
+        let inputs = [(base, compute_output(base, exp), exp)];
+        let prove = |seed| {
+            prove_batch(&params, &pk, &inputs, ChaCha20Rng::seed_from_u64(seed))
+                .expect("proof generation failed")
+        };
+
+        // the blinding factors are the only randomness of the prover
+        assert_eq!(prove(1), prove(1));
+        assert_ne!(prove(1), prove(2));
+    }
+
+    // Golden proof for base = 2, exp = 3 with seeded params and blinding, so that a change of
+    // layout, gate order or transcript shows up as changed bytes. A deliberate change is
+    // recorded with `UPDATE_GOLDEN=1 cargo test golden_proof_test`, the only way the file is
+    // written: a missing file fails, so a fresh checkout cannot pass by recording its own proof.
+    // Ignored until tests/data/golden_proof.bin is checked in, see tests/data/README.md.
+    #[cfg(feature = "std")]
+    #[test]
+    #[ignore = "tests/data/golden_proof.bin is not recorded yet"]
+    fn golden_proof_test() {
+        use std::{env, fs, path::Path};
+
+        let exp = 3;
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), ChaCha20Rng::seed_from_u64(0));
+
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+
+        let base = Fr::from(2);
+        let inputs = [(base, compute_output(base, exp), exp)];
+        let proof = prove_batch(&params, &pk, &inputs, ChaCha20Rng::seed_from_u64(1))
+            .expect("proof generation failed");
+
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/golden_proof.bin");
+        if env::var_os("UPDATE_GOLDEN").is_some() {
+            fs::create_dir_all(path.parent().unwrap()).expect("Failed to create tests/data");
+            fs::write(&path, &proof).expect("Failed to write golden proof");
+            return;
+        }
+
+        let golden = fs::read(&path).unwrap_or_else(|e| {
+            panic!(
+                "Failed to read golden proof {}: {}, record it with UPDATE_GOLDEN=1",
+                path.display(),
+                e
+            )
+        });
+        assert!(
+            proof == golden,
+            "proof bytes differ from {}, rerun with UPDATE_GOLDEN=1 if the change is intended",
+            path.display()
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn prove_with_timeout_test() {
+        use super::{prove_cancellable, prove_with_timeout, CancelToken, ProveError};
+        use crate::verify::verify_power_proof;
+        use std::{sync::Arc, time::Duration};
+
+        let exp = 3;
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), OsRng);
+
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+        let (params, pk) = (Arc::new(params), Arc::new(pk));
+
+        let base = Fr::from(2);
+        let statement = (base, compute_output(base, exp), exp);
+
+        // no proof is done within a nanosecond
+        let result = prove_with_timeout(
+            params.clone(),
+            pk.clone(),
+            statement,
+            Duration::from_nanos(1),
+        );
+        assert!(matches!(result, Err(ProveError::Timeout)));
+
+        let token = CancelToken::default();
+        token.cancel();
+        let result = prove_cancellable(params.clone(), pk.clone(), statement, &token, None);
+        assert!(matches!(result, Err(ProveError::Cancelled)));
+
+        let proof = prove_with_timeout(
+            params.clone(),
+            pk.clone(),
+            statement,
+            Duration::from_secs(600),
+        )
+        .expect("proof generation failed");
+        let instances = [statement.0, statement.1];
+        assert!(verify_power_proof(&params, pk.get_vk(), &instances, &proof).unwrap());
+    }
+
+    #[test]
+    fn create_power_proof_test() {
+        let exp = 3;
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), OsRng);
+
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+
+        let proof = create_power_proof(&params, &pk, Fr::from(2), exp, OsRng)
+            .expect("proof generation failed");
+        assert_eq!(proof.instances, vec![Fr::from(2), Fr::from(8)]);
+        assert!(proof.verify(&params, pk.get_vk()).unwrap());
+
+        // a bundle claiming another output is rejected
+        let mut forged = proof.clone();
+        forged.instances[1] = Fr::from(9);
+        assert!(!matches!(forged.verify(&params, pk.get_vk()), Ok(true)));
+    }
+
+    #[test]
+    fn same_statement_test() {
+        let exp = 3;
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), OsRng);
+
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+
+        let prove = |base: u64| {
+            create_power_proof(&params, &pk, Fr::from(base), exp, OsRng)
+                .expect("proof generation failed")
+        };
+
+        // two proofs of 2^3 = 8 differ in bytes, not in statement
+        let (proof, again) = (prove(2), prove(2));
+        assert_ne!(proof.bytes, again.bytes);
+        assert!(proof.same_statement(&again));
+
+        assert!(!proof.same_statement(&prove(3)));
+
+        let mut other_k = again;
+        other_k.k += 1;
+        assert!(!proof.same_statement(&other_k));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn power_proof_serde_test() {
+        use super::PowerProof;
+
+        let exp = 3;
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), OsRng);
+
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+
+        let proof = create_power_proof(&params, &pk, Fr::from(2), exp, OsRng)
+            .expect("proof generation failed");
+        let json = serde_json::to_string(&proof).unwrap();
+        assert!(json.contains(r#""instances":["2","8"]"#));
+
+        let reread: PowerProof = serde_json::from_str(&json).unwrap();
+        assert_eq!(reread, proof);
+        assert!(reread.verify(&params, pk.get_vk()).unwrap());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn prove_power_async_test() {
+        use super::prove_power_async;
+        use crate::verify::verify_power_proof;
+        use std::sync::Arc;
+
+        let exp = 3;
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), OsRng);
+
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+
+        let (params, pk) = (Arc::new(params), Arc::new(pk));
+        let (base, result) = (Fr::from(2), Fr::from(8));
+        let proof = prove_power_async(params.clone(), pk.clone(), base, result, exp)
+            .await
+            .expect("proof generation failed");
+
+        let accept = verify_power_proof(&params, pk.get_vk(), &[base, result], &proof)
+            .expect("proof verification failed");
+        assert!(accept);
+    }
+}
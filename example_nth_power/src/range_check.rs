@@ -0,0 +1,185 @@
+// Lookup-based range check on a private exponent.
+// It proves the relation R = { ( x, y; exp ): x^exp = y, exp <= MAX_EXP } with the
+// square-and-multiply ladder, which witnesses exp as bits and recomposes it into a cell. That
+// cell is looked up in a fixed table holding 0..=MAX_EXP, so a prover cannot pick a larger
+// exponent, even one that fits the bits of the ladder.
+use crate::square_multiply::{SquareMultiplyChip, SquareMultiplyConfig};
+use alloc::vec;
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+pub const MAX_EXP: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct ExpRangeConfig {
+    pub exp: Column<Advice>,
+    pub selector: Selector,
+    pub table: TableColumn,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExpRangeChip<F: PrimeField> {
+    config: ExpRangeConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> ExpRangeChip<F> {
+    pub fn construct(config: ExpRangeConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> ExpRangeConfig {
+        let exp = meta.advice_column();
+        // selectors used in lookups can not be simple selectors
+        let selector = meta.complex_selector();
+        let table = meta.lookup_table_column();
+
+        meta.enable_equality(exp);
+
+        // on disabled rows the looked up value is 0, which the table contains
+        meta.lookup("exp range", |meta| {
+            let s = meta.query_selector(selector);
+            let exp = meta.query_advice(exp, Rotation::cur());
+            vec![(s * exp, table)]
+        });
+
+        ExpRangeConfig {
+            exp,
+            selector,
+            table,
+        }
+    }
+
+    pub fn load_table(&self, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "exp table",
+            |mut table| {
+                for i in 0..=MAX_EXP {
+                    table.assign_cell(
+                        || "exp",
+                        self.config.table,
+                        i,
+                        || Value::known(F::from(i as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    // Looks up an exponent cell, e.g. the one a ladder recomposes from its private bits.
+    pub fn check_exp(
+        &self,
+        mut layouter: impl Layouter<F>,
+        exp: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "exp",
+            |mut region| {
+                self.config.selector.enable(&mut region, 0)?;
+
+                exp.copy_advice(|| "exp", &mut region, self.config.exp, 0)?;
+                Ok(())
+            },
+        )
+    }
+}
+
+// Same statement as square_multiply::HiddenExponentCircuit, x^exp = y with a private exp, plus
+// exp <= MAX_EXP. `num_bits` is structural and may allow larger exponents, which the lookup
+// rejects.
+// Public instance: [x, y].
+#[derive(Default, Clone)]
+pub struct RangeCheckedPowerCircuit<F> {
+    pub exp: Value<u64>,
+    pub num_bits: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> RangeCheckedPowerCircuit<F> {
+    pub fn new(exp: Value<u64>, num_bits: usize) -> Self {
+        Self {
+            exp,
+            num_bits,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for RangeCheckedPowerCircuit<F> {
+    type Config = (SquareMultiplyConfig, ExpRangeConfig);
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(Value::unknown(), self.num_bits)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        (
+            SquareMultiplyChip::configure(meta),
+            ExpRangeChip::configure(meta),
+        )
+    }
+
+    fn synthesize(
+        &self,
+        (config, range_config): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = SquareMultiplyChip::construct(config);
+        let range_chip = ExpRangeChip::construct(range_config);
+
+        range_chip.load_table(layouter.namespace(|| "exp table"))?;
+
+        // the exponent only exists as the ladder's bits and their recomposition
+        let (result, exp) = chip.assign(
+            layouter.namespace(|| "square and multiply"),
+            self.exp,
+            self.num_bits,
+        )?;
+        range_chip.check_exp(layouter.namespace(|| "exp range"), &exp)?;
+
+        chip.expose_public(layouter.namespace(|| "out"), &result, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RangeCheckedPowerCircuit, MAX_EXP};
+    use crate::example2::compute_output;
+    use halo2::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr};
+
+    // wide enough for exponents past MAX_EXP
+    const NUM_BITS: usize = 8;
+
+    fn verifies(exp: usize, y: Fr) -> bool {
+        let k = 6;
+        let circuit = RangeCheckedPowerCircuit::new(Value::known(exp as u64), NUM_BITS);
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(2), y]]).unwrap();
+        prover.verify().is_ok()
+    }
+
+    #[test]
+    fn range_check_test() {
+        let input = Fr::from(2);
+
+        // an exponent inside 0..=MAX_EXP passes, both ends included
+        for exp in [5, 0, MAX_EXP] {
+            assert!(verifies(exp, compute_output(input, exp)), "exp = {}", exp);
+        }
+
+        // exp = 0 does not accept the base itself as the output
+        assert!(!verifies(0, input));
+
+        // a private exponent above MAX_EXP fits the bits, but is not found in the table
+        let exp = MAX_EXP + 4;
+        assert!(!verifies(exp, compute_output(input, exp)));
+    }
+}
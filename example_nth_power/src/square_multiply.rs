@@ -0,0 +1,374 @@
+// Square-and-multiply (binary) exponentiation.
+// It proves the relation R = { ( x, y, exp; ): x^exp = y } with O(log exp) rows instead of the
+// O(exp) rows of the naive chain in example2. The exponent is decomposed into `num_bits` boolean
+// cells, most significant bit first, and one region of num_bits + 1 rows is laid out:
+//
+// | acc        | bit   | base | e          |
+// | 1          | b_0   | x    | 0          |
+// | acc_0' ... | b_1   | x    | b_0   ...  |
+// | y          |       | x    | exp        |
+//
+// where every enabled row squares the accumulator and multiplies by x when the bit is set,
+// and `e` recomposes the exponent from its bits.
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+pub struct SquareMultiplyConfig {
+    pub col_acc: Column<Advice>,
+    pub col_bit: Column<Advice>,
+    pub col_base: Column<Advice>,
+    pub col_exp: Column<Advice>,
+    pub selector: Selector,
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SquareMultiplyChip<F: PrimeField> {
+    config: SquareMultiplyConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> SquareMultiplyChip<F> {
+    pub fn construct(config: SquareMultiplyConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> SquareMultiplyConfig {
+        let col_acc = meta.advice_column();
+        let col_bit = meta.advice_column();
+        let col_base = meta.advice_column();
+        let col_exp = meta.advice_column();
+        let selector = meta.selector();
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+
+        meta.enable_equality(col_acc);
+        meta.enable_equality(col_base);
+        meta.enable_equality(col_exp);
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate("square and multiply", |meta| {
+            let s = meta.query_selector(selector);
+            let acc = meta.query_advice(col_acc, Rotation::cur());
+            let acc_next = meta.query_advice(col_acc, Rotation::next());
+            let bit = meta.query_advice(col_bit, Rotation::cur());
+            let base = meta.query_advice(col_base, Rotation::cur());
+            let base_next = meta.query_advice(col_base, Rotation::next());
+            let exp = meta.query_advice(col_exp, Rotation::cur());
+            let exp_next = meta.query_advice(col_exp, Rotation::next());
+
+            let one = Expression::Constant(F::ONE);
+            // multiply by the base when the bit is set, by 1 otherwise
+            let factor = bit.clone() * base.clone() + (one.clone() - bit.clone());
+
+            vec![
+                s.clone() * bit.clone() * (one - bit.clone()),
+                s.clone() * (acc_next - acc.clone() * acc * factor),
+                s.clone() * (exp_next - exp * F::from(2) - bit),
+                s * (base_next - base),
+            ]
+        });
+
+        SquareMultiplyConfig {
+            col_acc,
+            col_bit,
+            col_base,
+            col_exp,
+            selector,
+            instance,
+            constant,
+        }
+    }
+
+    // Lays out the ladder for `exp` with the base taken from instance row 0.
+    // Returns the cells holding the result and the recomposed exponent.
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        exp: Value<u64>,
+        num_bits: usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let (result, mut prefixes) = self.assign_with_prefixes(layouter, exp, num_bits)?;
+        Ok((result, prefixes.pop().unwrap()))
+    }
+
+    // Same as `assign`, returning the exponent recomposed after each bit as
+    // `assign_private_base` does.
+    pub fn assign_with_prefixes(
+        &self,
+        layouter: impl Layouter<F>,
+        exp: Value<u64>,
+        num_bits: usize,
+    ) -> Result<(AssignedCell<F, F>, Vec<AssignedCell<F, F>>), Error> {
+        self.assign_ladder(
+            layouter,
+            |region| {
+                region.assign_advice_from_instance(
+                    || "base",
+                    self.config.instance,
+                    0,
+                    self.config.col_base,
+                    0,
+                )
+            },
+            exp,
+            num_bits,
+        )
+    }
+
+    // Same ladder with a private base. Returns the result and the exponent recomposed after
+    // each bit: entry i holds exp >> (num_bits - i), the last one exp itself.
+    pub fn assign_private_base(
+        &self,
+        layouter: impl Layouter<F>,
+        base: Value<F>,
+        exp: Value<u64>,
+        num_bits: usize,
+    ) -> Result<(AssignedCell<F, F>, Vec<AssignedCell<F, F>>), Error> {
+        self.assign_ladder(
+            layouter,
+            |region| region.assign_advice(|| "base", self.config.col_base, 0, || base),
+            exp,
+            num_bits,
+        )
+    }
+
+    // Same ladder with the base copied from a cell, e.g. one range checked by another chip.
+    pub fn assign_copied_base(
+        &self,
+        layouter: impl Layouter<F>,
+        base: &AssignedCell<F, F>,
+        exp: Value<u64>,
+        num_bits: usize,
+    ) -> Result<(AssignedCell<F, F>, Vec<AssignedCell<F, F>>), Error> {
+        self.assign_ladder(
+            layouter,
+            |region| base.copy_advice(|| "base", region, self.config.col_base, 0),
+            exp,
+            num_bits,
+        )
+    }
+
+    fn assign_ladder(
+        &self,
+        mut layouter: impl Layouter<F>,
+        load_base: impl Fn(&mut Region<'_, F>) -> Result<AssignedCell<F, F>, Error>,
+        exp: Value<u64>,
+        num_bits: usize,
+    ) -> Result<(AssignedCell<F, F>, Vec<AssignedCell<F, F>>), Error> {
+        layouter.assign_region(
+            || "square and multiply",
+            |mut region| {
+                let base = load_base(&mut region)?;
+                let base = base.value().copied();
+
+                let mut acc =
+                    region.assign_advice_from_constant(|| "acc", self.config.col_acc, 0, F::ONE)?;
+                let mut exp_acc =
+                    region.assign_advice_from_constant(|| "e", self.config.col_exp, 0, F::ZERO)?;
+                let mut prefixes = vec![exp_acc.clone()];
+
+                for i in 0..num_bits {
+                    self.config.selector.enable(&mut region, i)?;
+
+                    let bit = exp.map(|e| (e >> (num_bits - 1 - i)) & 1 == 1);
+                    region.assign_advice(
+                        || "bit",
+                        self.config.col_bit,
+                        i,
+                        || bit.map(|b| F::from(b as u64)),
+                    )?;
+
+                    region.assign_advice(|| "base", self.config.col_base, i + 1, || base)?;
+
+                    let acc_next = acc.value().zip(bit).zip(base).map(|((acc, bit), base)| {
+                        let square = acc.square();
+                        if bit {
+                            square * base
+                        } else {
+                            square
+                        }
+                    });
+                    acc =
+                        region.assign_advice(|| "acc", self.config.col_acc, i + 1, || acc_next)?;
+
+                    let exp_next = exp_acc
+                        .value()
+                        .zip(bit)
+                        .map(|(e, bit)| e.double() + F::from(bit as u64));
+                    exp_acc =
+                        region.assign_advice(|| "e", self.config.col_exp, i + 1, || exp_next)?;
+                    prefixes.push(exp_acc.clone());
+                }
+
+                Ok((acc, prefixes))
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+// Public instance: [x, y, exp]. `num_bits` is structural and bounds exp below 2^num_bits.
+#[derive(Default, Clone)]
+pub struct SquareMultiplyCircuit<F> {
+    pub exp: Value<u64>,
+    pub num_bits: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> SquareMultiplyCircuit<F> {
+    pub fn new(exp: Value<u64>, num_bits: usize) -> Self {
+        Self {
+            exp,
+            num_bits,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for SquareMultiplyCircuit<F> {
+    type Config = SquareMultiplyConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(Value::unknown(), self.num_bits)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        SquareMultiplyChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = SquareMultiplyChip::construct(config);
+
+        let (result, exp) = chip.assign(
+            layouter.namespace(|| "square and multiply"),
+            self.exp,
+            self.num_bits,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "out"), &result, 1)?;
+        chip.expose_public(layouter.namespace(|| "exp"), &exp, 2)?;
+
+        Ok(())
+    }
+}
+
+// Public instance: [x, y]. Proves knowledge of some exp below 2^num_bits with x^exp = y,
+// discrete log style: the exponent is only ever witnessed as bits and never exposed.
+#[derive(Default, Clone)]
+pub struct HiddenExponentCircuit<F> {
+    pub exp: Value<u64>,
+    pub num_bits: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> HiddenExponentCircuit<F> {
+    pub fn new(exp: Value<u64>, num_bits: usize) -> Self {
+        Self {
+            exp,
+            num_bits,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for HiddenExponentCircuit<F> {
+    type Config = SquareMultiplyConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(Value::unknown(), self.num_bits)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        SquareMultiplyChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = SquareMultiplyChip::construct(config);
+
+        // the recomposed exponent stays a private cell
+        let (result, _) = chip.assign(
+            layouter.namespace(|| "square and multiply"),
+            self.exp,
+            self.num_bits,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "out"), &result, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HiddenExponentCircuit, SquareMultiplyCircuit};
+    use crate::example2::{compute_output, min_k, TestCircuit};
+    use halo2::{circuit::Value, dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn square_multiply_test() {
+        let k = 4;
+        let num_bits = 8;
+        let input = Fr::from(3);
+
+        // both chips agree on x^exp
+        for exp in [0, 1, 2, 3, 5, 8, 13] {
+            let output = compute_output(input, exp);
+
+            let naive = TestCircuit::new(exp);
+            let prover = MockProver::run(min_k(exp), &naive, vec![vec![input, output]]).unwrap();
+            prover.assert_satisfied();
+
+            let circuit = SquareMultiplyCircuit::new(Value::known(exp as u64), num_bits);
+            let public_input = vec![input, output, Fr::from(exp as u64)];
+            let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn hidden_exponent_test() {
+        let k = 4;
+        let num_bits = 8;
+
+        // only x = 2 and y = 8 are public, exp = 3 is the witness
+        let public_input = vec![Fr::from(2), Fr::from(8)];
+        let circuit = HiddenExponentCircuit::new(Value::known(3), num_bits);
+        let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        // 2^2 is not 8
+        let circuit = HiddenExponentCircuit::new(Value::known(2), num_bits);
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,179 @@
+// Power chain with a step counter.
+// It proves the relation R = { ( x, y, exp; ): x^exp = y } where the number of multiplication
+// rows is itself proven to be the public exp. The chain of example2 is laid out in one region
+// with a counter next to it:
+//
+// | a         | b | c     | count |
+// | 1         | x | x     | 1     |
+// | x         | x | x^2   | 2     |
+// | ...       | x | ...   | ...   |
+// | x^(exp-1) | x | x^exp | exp   |
+//
+// The mul gate holds on every row, s_step * (count_next - count_cur - 1) on all but the last,
+// the first count is the constant 1 and the last one is copied to the instance.
+use crate::example2::{PowerByNumChip, PowerByNumConfig};
+use alloc::vec;
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+pub struct StepCountConfig {
+    pub power: PowerByNumConfig,
+    pub count: Column<Advice>,
+    pub step: Selector,
+}
+
+#[derive(Debug, Clone)]
+pub struct StepCountChip<F: PrimeField> {
+    config: StepCountConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> StepCountChip<F> {
+    pub fn construct(config: StepCountConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> StepCountConfig {
+        let power = PowerByNumChip::configure(meta);
+        let count = meta.advice_column();
+        let step = meta.selector();
+
+        meta.enable_equality(count);
+
+        meta.create_gate("step", |meta| {
+            let s = meta.query_selector(step);
+            let count_cur = meta.query_advice(count, Rotation::cur());
+            let count_next = meta.query_advice(count, Rotation::next());
+            vec![s * (count_next - count_cur - Expression::Constant(F::ONE))]
+        });
+
+        StepCountConfig { power, count, step }
+    }
+
+    // Lays out `exp` >= 1 rows with the base from instance row 0.
+    // Returns the cells holding x^exp and the final count.
+    pub fn assign_chain(
+        &self,
+        mut layouter: impl Layouter<F>,
+        exp: usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let config = &self.config.power;
+
+        layouter.assign_region(
+            || "counted chain",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                let one = region.assign_advice_from_constant(|| "1", config.col_a, 0, F::ONE)?;
+                let base = region.assign_advice_from_instance(
+                    || "x",
+                    config.instance,
+                    0,
+                    config.col_b,
+                    0,
+                )?;
+                let mut power = region.assign_advice(
+                    || "x",
+                    config.col_c,
+                    0,
+                    || one.value().copied() * base.value(),
+                )?;
+                let mut count =
+                    region.assign_advice_from_constant(|| "count", self.config.count, 0, F::ONE)?;
+
+                for row in 1..exp {
+                    config.selector.enable(&mut region, row)?;
+                    self.config.step.enable(&mut region, row - 1)?;
+
+                    let prev = power.copy_advice(|| "x^(i-1)", &mut region, config.col_a, row)?;
+                    base.copy_advice(|| "x", &mut region, config.col_b, row)?;
+                    power = region.assign_advice(
+                        || "x^i",
+                        config.col_c,
+                        row,
+                        || prev.value().copied() * base.value(),
+                    )?;
+                    count = region.assign_advice(
+                        || "count",
+                        self.config.count,
+                        row,
+                        || count.value().copied() + Value::known(F::ONE),
+                    )?;
+                }
+
+                Ok((power, count))
+            },
+        )
+    }
+}
+
+// Public instance: [x, y, exp]. `exp` is structural and must be at least 1.
+#[derive(Default, Clone)]
+pub struct StepCountCircuit<F> {
+    pub exp: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> StepCountCircuit<F> {
+    pub fn new(exp: usize) -> Self {
+        Self {
+            exp,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for StepCountCircuit<F> {
+    type Config = StepCountConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.exp)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        StepCountChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = StepCountChip::construct(config.clone());
+
+        let (power, count) = chip.assign_chain(layouter.namespace(|| "chain"), self.exp)?;
+
+        layouter.constrain_instance(power.cell(), config.power.instance, 1)?;
+        layouter.constrain_instance(count.cell(), config.power.instance, 2)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StepCountCircuit;
+    use halo2::{dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn step_count_test() {
+        let k = 4;
+        let circuit = StepCountCircuit::<Fr>::new(5);
+
+        // 2^5 = 32 in 5 steps
+        let public_input = vec![Fr::from(2), Fr::from(32), Fr::from(5)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // the same chain does not pass for a claimed exponent of 4
+        let public_input = vec![Fr::from(2), Fr::from(32), Fr::from(4)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
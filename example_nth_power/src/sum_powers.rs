@@ -0,0 +1,156 @@
+// Sum of powers.
+// It proves the relation R = { ( x, s; n): x + x^2 + ... + x^n = s } by running the power
+// chain of example2 and adding every power into a running sum. Two accumulators travel across
+// regions: the current power, fed back into the mul gate, and the sum, fed into an add gate
+// s * (a + b - c) placed on the same three columns.
+use crate::example2::{ChainMode, PowerByNumChip, PowerByNumConfig};
+use alloc::vec;
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*, poly::Rotation};
+
+#[derive(Debug, Clone)]
+pub struct SumOfPowersConfig {
+    pub power: PowerByNumConfig,
+    pub add_selector: Selector,
+}
+
+#[derive(Debug, Clone)]
+pub struct SumOfPowersChip<F: PrimeField> {
+    config: SumOfPowersConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> SumOfPowersChip<F> {
+    pub fn construct(config: SumOfPowersConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> SumOfPowersConfig {
+        let power = PowerByNumChip::configure(meta);
+        let add_selector = meta.selector();
+
+        meta.create_gate("add", |meta| {
+            let s = meta.query_selector(add_selector);
+            let a = meta.query_advice(power.col_a, Rotation::cur());
+            let b = meta.query_advice(power.col_b, Rotation::cur());
+            let c = meta.query_advice(power.col_c, Rotation::cur());
+            vec![s * (a + b - c)]
+        });
+
+        SumOfPowersConfig {
+            power,
+            add_selector,
+        }
+    }
+
+    pub fn power_chip(&self) -> PowerByNumChip<F> {
+        PowerByNumChip::construct(self.config.power.clone())
+    }
+
+    // Returns a new cell holding sum + power.
+    pub fn add_cells(
+        &self,
+        mut layouter: impl Layouter<F>,
+        sum: &AssignedCell<F, F>,
+        power: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "add",
+            |mut region| {
+                self.config.add_selector.enable(&mut region, 0)?;
+
+                sum.copy_advice(|| "sum", &mut region, self.config.power.col_a, 0)?;
+                power.copy_advice(|| "power", &mut region, self.config.power.col_b, 0)?;
+
+                region.assign_advice(
+                    || "sum + power",
+                    self.config.power.col_c,
+                    0,
+                    || sum.value().copied() + power.value(),
+                )
+            },
+        )
+    }
+}
+
+// Public instance: [x, s]. `n` is structural and must be at least 1.
+#[derive(Default, Clone)]
+pub struct SumOfPowersCircuit<F> {
+    pub n: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> SumOfPowersCircuit<F> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for SumOfPowersCircuit<F> {
+    type Config = SumOfPowersConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.n)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        SumOfPowersChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = SumOfPowersChip::construct(config);
+        let power_chip = chip.power_chip();
+
+        // x^1 starts both the power and the sum
+        let (_, base, mut power) = power_chip.intial_assign(layouter.namespace(|| "x"))?;
+        let mut sum = power.clone();
+
+        for i in 1..self.n {
+            power = power_chip.subsequent_assign(
+                layouter.namespace(|| "next power"),
+                i,
+                ChainMode::MultiplyByBase,
+                &base,
+                &power,
+            )?;
+            sum = chip.add_cells(layouter.namespace(|| "running sum"), &sum, &power)?;
+        }
+
+        power_chip.expose_public(layouter.namespace(|| "out"), &sum, 1)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SumOfPowersCircuit;
+    use halo2::{dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn sum_of_powers_test() {
+        let k = 4;
+
+        // 2 + 4 + 8 = 14
+        let circuit = SumOfPowersCircuit::new(3);
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(2), Fr::from(14)]]).unwrap();
+        prover.assert_satisfied();
+
+        // a sum off by one is rejected
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(2), Fr::from(13)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
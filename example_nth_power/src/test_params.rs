@@ -0,0 +1,129 @@
+// Inputs of the power tests, overridable from the environment so that CI can run the same
+// tests over a matrix of statements, e.g. `POWER_BASE=3 POWER_EXP=7 cargo test`.
+// The base is read like the CLI reads it, as a decimal or 0x-prefixed hex element, and the
+// output is computed natively from base and exponent.
+use crate::example2::{compute_output, instances, min_k};
+use crate::parse::fr_from_str;
+use halo2::halo2curves::bn256::Fr;
+use std::env;
+
+pub const DEFAULT_BASE: u64 = 2;
+pub const DEFAULT_EXP: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestParams {
+    pub base: Fr,
+    pub exp: usize,
+    pub output: Fr,
+}
+
+impl TestParams {
+    pub fn new(base: Fr, exp: usize) -> Self {
+        Self {
+            base,
+            exp,
+            output: compute_output(base, exp),
+        }
+    }
+
+    // Reads POWER_BASE and POWER_EXP, falling back to 2^3 for unset ones. A set but malformed
+    // value panics: a typo in the CI matrix should not silently test the defaults.
+    pub fn from_env() -> Self {
+        let base = match env::var("POWER_BASE") {
+            Ok(base) => fr_from_str(&base)
+                .unwrap_or_else(|e| panic!("invalid POWER_BASE `{}`: {}", base, e)),
+            Err(_) => Fr::from(DEFAULT_BASE),
+        };
+        let exp = match env::var("POWER_EXP") {
+            Ok(exp) => exp
+                .parse()
+                .unwrap_or_else(|_| panic!("invalid POWER_EXP `{}`", exp)),
+            Err(_) => DEFAULT_EXP,
+        };
+
+        Self::new(base, exp)
+    }
+
+    pub fn k(&self) -> u32 {
+        min_k(self.exp)
+    }
+
+    // The instance columns of example2::TestCircuit for this statement.
+    pub fn instances(&self) -> Vec<Vec<Fr>> {
+        instances(self.base, self.output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TestParams, DEFAULT_BASE, DEFAULT_EXP};
+    use crate::example2::TestCircuit;
+    use halo2::{dev::MockProver, halo2curves::bn256::Fr};
+    use std::{
+        env,
+        ffi::OsString,
+        sync::{Mutex, MutexGuard},
+    };
+
+    // The environment is shared by the whole test binary, so the tests touching it take turns.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    // Sets (or removes) the variables for the lifetime of the guard, then restores them.
+    struct EnvGuard {
+        saved: Vec<(&'static str, Option<OsString>)>,
+        _lock: MutexGuard<'static, ()>,
+    }
+
+    impl EnvGuard {
+        fn new(vars: &[(&'static str, Option<&str>)]) -> Self {
+            let lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let saved = vars
+                .iter()
+                .map(|&(name, value)| {
+                    let old = env::var_os(name);
+                    match value {
+                        Some(value) => env::set_var(name, value),
+                        None => env::remove_var(name),
+                    }
+                    (name, old)
+                })
+                .collect();
+
+            Self { saved, _lock: lock }
+        }
+    }
+
+    impl Drop for EnvGuard {
+        fn drop(&mut self) {
+            for (name, old) in &self.saved {
+                match old {
+                    Some(old) => env::set_var(name, old),
+                    None => env::remove_var(name),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn default_params_test() {
+        let _guard = EnvGuard::new(&[("POWER_BASE", None), ("POWER_EXP", None)]);
+
+        let params = TestParams::from_env();
+        assert_eq!(params.base, Fr::from(DEFAULT_BASE));
+        assert_eq!(params.exp, DEFAULT_EXP);
+        assert_eq!(params.output, Fr::from(8));
+    }
+
+    #[test]
+    fn env_params_test() {
+        let _guard = EnvGuard::new(&[("POWER_BASE", Some("0x3")), ("POWER_EXP", Some("4"))]);
+
+        let params = TestParams::from_env();
+        assert_eq!(params, TestParams::new(Fr::from(3), 4));
+        assert_eq!(params.output, Fr::from(81));
+
+        let circuit = TestCircuit::<Fr>::new(params.exp);
+        let prover = MockProver::run(params.k(), &circuit, params.instances()).unwrap();
+        prover.assert_satisfied();
+    }
+}
@@ -0,0 +1,164 @@
+// Repeated squaring, the evaluation of a VDF (verifiable delay function).
+// It proves the relation R = { ( x, t, y; ): x^(2^t) = y }. Computing y takes t sequential
+// squarings, while checking the proof does not. The chain of example2 runs in `Square` mode in
+// one region, with the step counter of step_count next to it, this time starting from 0:
+//
+// | a           | b           | c       | count |
+// | 1           | x           | x       | 0     |
+// | x           | x           | x^2     | 1     |
+// | ...         | ...         | ...     | ...   |
+// | x^(2^(t-1)) | x^(2^(t-1)) | x^(2^t) | t     |
+//
+// so the last count, copied to the instance, pins the number of squarings to the public t.
+use crate::step_count::{StepCountChip, StepCountConfig};
+use core::marker::PhantomData;
+use halo2::{circuit::*, halo2curves::ff::PrimeField, plonk::*};
+
+#[derive(Debug, Clone)]
+pub struct VdfChip<F: PrimeField> {
+    config: StepCountConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> VdfChip<F> {
+    pub fn construct(config: StepCountConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    // Same gates as the step counter: the mul gate on every row, the count step on all but
+    // the last.
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> StepCountConfig {
+        StepCountChip::configure(meta)
+    }
+
+    // Lays out `t` squarings with the base from instance row 0.
+    // Returns the cells holding x^(2^t) and the final count.
+    pub fn assign_squarings(
+        &self,
+        mut layouter: impl Layouter<F>,
+        t: usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let config = &self.config.power;
+
+        layouter.assign_region(
+            || "squarings",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                let one = region.assign_advice_from_constant(|| "1", config.col_a, 0, F::ONE)?;
+                let base = region.assign_advice_from_instance(
+                    || "x",
+                    config.instance,
+                    0,
+                    config.col_b,
+                    0,
+                )?;
+                let mut power = region.assign_advice(
+                    || "x",
+                    config.col_c,
+                    0,
+                    || one.value().copied() * base.value(),
+                )?;
+                let mut count = region.assign_advice_from_constant(
+                    || "count",
+                    self.config.count,
+                    0,
+                    F::ZERO,
+                )?;
+
+                for row in 1..=t {
+                    config.selector.enable(&mut region, row)?;
+                    self.config.step.enable(&mut region, row - 1)?;
+
+                    let prev =
+                        power.copy_advice(|| "x^(2^(i-1))", &mut region, config.col_a, row)?;
+                    power.copy_advice(|| "x^(2^(i-1))", &mut region, config.col_b, row)?;
+                    power = region.assign_advice(
+                        || "x^(2^i)",
+                        config.col_c,
+                        row,
+                        || prev.value().map(|prev| prev.square()),
+                    )?;
+                    count = region.assign_advice(
+                        || "count",
+                        self.config.count,
+                        row,
+                        || count.value().copied() + Value::known(F::ONE),
+                    )?;
+                }
+
+                Ok((power, count))
+            },
+        )
+    }
+}
+
+// Public instance: [x, t, y]. `t` is structural, one circuit per delay.
+#[derive(Default, Clone)]
+pub struct VdfCircuit<F> {
+    pub t: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F> VdfCircuit<F> {
+    pub fn new(t: usize) -> Self {
+        Self {
+            t,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for VdfCircuit<F> {
+    type Config = StepCountConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+    #[cfg(feature = "circuit-params")]
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::new(self.t)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        VdfChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = VdfChip::construct(config.clone());
+
+        let (power, count) = chip.assign_squarings(layouter.namespace(|| "squarings"), self.t)?;
+
+        layouter.constrain_instance(count.cell(), config.power.instance, 1)?;
+        layouter.constrain_instance(power.cell(), config.power.instance, 2)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VdfCircuit;
+    use halo2::{dev::MockProver, halo2curves::bn256::Fr};
+
+    #[test]
+    fn vdf_test() {
+        let k = 4;
+        let circuit = VdfCircuit::<Fr>::new(5);
+
+        // 2^(2^5) = 2^32 in 5 squarings
+        let public_input = vec![Fr::from(2), Fr::from(5), Fr::from(1u64 << 32)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        prover.assert_satisfied();
+
+        // the same squarings do not pass for a claimed delay of 4
+        let public_input = vec![Fr::from(2), Fr::from(4), Fr::from(1u64 << 32)];
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,356 @@
+// KZG verification helpers for the example2 power circuit.
+use crate::logging::stage;
+use alloc::vec::Vec;
+use halo2::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{verify_proof, Error, VerifyingKey},
+    poly::{
+        commitment::ParamsProver,
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::VerifierGWC,
+            strategy::AccumulatorStrategy,
+        },
+        VerificationStrategy,
+    },
+    transcript::{
+        Blake2bRead, Challenge255, EncodedChallenge, TranscriptRead, TranscriptReadBuffer,
+    },
+};
+
+// Verifies a single-circuit proof against its instance column.
+// A malformed proof surfaces as `Err`, a well-formed but invalid one as `Ok(false)`.
+pub fn verify_power_proof(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[Fr],
+    proof: &[u8],
+) -> Result<bool, Error> {
+    verify_power_proof_with_transcript::<Challenge255<G1Affine>, Blake2bRead<_, _, _>>(
+        params, vk, instances, proof,
+    )
+}
+
+// `verify_power_proof` for proofs written with another transcript, see
+// `prove_batch_with_transcript`.
+pub fn verify_power_proof_with_transcript<
+    'a,
+    E: EncodedChallenge<G1Affine>,
+    T: TranscriptReadBuffer<&'a [u8], G1Affine, E>,
+>(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[Fr],
+    proof: &'a [u8],
+) -> Result<bool, Error> {
+    let mut transcript = T::init(proof);
+    verify_transcript(params, vk, instances, &mut transcript)
+}
+
+// Same as `verify_power_proof`, but the proof is read as the transcript goes instead of being
+// buffered whole first.
+#[cfg(feature = "std")]
+pub fn verify_power_proof_from_reader(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[Fr],
+    proof: impl std::io::Read,
+) -> Result<bool, Error> {
+    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+    verify_transcript(params, vk, instances, &mut transcript)
+}
+
+// Verifies proofs of possibly different circuits, e.g. a power and a fibonacci statement,
+// as one batch. Each (vk, instances, proof) is checked into the same AccumulatorStrategy, so
+// the pairing check runs once for the whole batch: one bad proof rejects all of them.
+pub fn batch_verify(
+    params: &ParamsKZG<Bn256>,
+    statements: &[(&VerifyingKey<G1Affine>, &[Fr], &[u8])],
+) -> Result<bool, Error> {
+    let mut strategy = AccumulatorStrategy::new(params.verifier_params());
+    for &(vk, instances, proof) in statements {
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+        strategy = verify_proof::<KZGCommitmentScheme<_>, VerifierGWC<_>, _, _, _>(
+            params.verifier_params(),
+            vk,
+            strategy,
+            &[&[instances]],
+            &mut transcript,
+        )?;
+    }
+
+    Ok(VerificationStrategy::<_, VerifierGWC<_>>::finalize(
+        strategy,
+    ))
+}
+
+// `batch_verify` for proofs of the same circuit, one proof per (base, output) statement.
+// Malformed proofs and a statement count not matching the proofs are rejected like invalid ones.
+pub fn verify_batch(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    statements: &[(Fr, Fr)],
+    proofs: &[Vec<u8>],
+) -> bool {
+    if statements.len() != proofs.len() {
+        return false;
+    }
+
+    let instances: Vec<[Fr; 2]> = statements
+        .iter()
+        .map(|&(base, output)| [base, output])
+        .collect();
+    let batch: Vec<_> = instances
+        .iter()
+        .zip(proofs)
+        .map(|(instances, proof)| (vk, &instances[..], &proof[..]))
+        .collect();
+
+    batch_verify(params, &batch).unwrap_or(false)
+}
+
+fn verify_transcript<E: EncodedChallenge<G1Affine>, T: TranscriptRead<G1Affine, E>>(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[Fr],
+    transcript: &mut T,
+) -> Result<bool, Error> {
+    stage("verify", || {
+        let strategy = verify_proof::<KZGCommitmentScheme<_>, VerifierGWC<_>, _, _, _>(
+            params.verifier_params(),
+            vk,
+            AccumulatorStrategy::new(params.verifier_params()),
+            &[&[instances]],
+            transcript,
+        )?;
+
+        Ok(VerificationStrategy::<_, VerifierGWC<_>>::finalize(
+            strategy,
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        batch_verify, verify_batch, verify_power_proof, verify_power_proof_with_transcript,
+    };
+    use crate::example2::{compute_output, min_k, TestCircuit};
+    use crate::fibonacci::FibonacciCircuit;
+    use crate::prove::{prove_batch, prove_batch_with_transcript};
+    use halo2::{
+        dev::MockProver,
+        halo2curves::bn256::{Bn256, Fr, G1Affine},
+        plonk::{create_proof, keygen_pk, keygen_vk, Circuit, ProvingKey},
+        poly::kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::ProverGWC,
+        },
+        transcript::{
+            Blake2bWrite, Challenge255, Keccak256Read, Keccak256Write, TranscriptWriterBuffer,
+        },
+    };
+    use rand::rngs::OsRng;
+
+    // Runs MockProver and the real KZG pipeline on the same statement and returns their common
+    // verdict. `create_proof` is called directly: it does not check the witness, so an
+    // unsatisfied circuit still gives a proof, one the verifier has to reject.
+    fn verdicts_agree(base: u64, exp: usize, output: u64) -> bool {
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let instances = [Fr::from(base), Fr::from(output)];
+
+        let prover = MockProver::run(min_k(exp), &circuit, vec![instances.to_vec()]).unwrap();
+        let mock_accepts = prover.verify().is_ok();
+
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), OsRng);
+        let pk = keygen(&params, &circuit);
+        let proof = prove(&params, &pk, circuit, &instances);
+        let kzg_accepts =
+            verify_power_proof(&params, pk.get_vk(), &instances, &proof).unwrap_or(false);
+
+        assert_eq!(
+            mock_accepts, kzg_accepts,
+            "MockProver and KZG disagree on {}^{} = {}",
+            base, exp, output
+        );
+        kzg_accepts
+    }
+
+    #[test]
+    fn mock_and_kzg_agree_test() {
+        assert!(verdicts_agree(2, 3, 8));
+        assert!(!verdicts_agree(2, 3, 9));
+    }
+
+    #[test]
+    fn verify_power_proof_test() {
+        let exp = 3;
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), OsRng);
+
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+
+        let base = Fr::from(2);
+        let output = compute_output(base, exp);
+        let mut proof = prove_batch(&params, &pk, &[(base, output, exp)], OsRng)
+            .expect("proof generation failed");
+
+        let instances = [base, output];
+        assert!(verify_power_proof(&params, pk.get_vk(), &instances, &proof).unwrap());
+
+        // a tampered byte is reported, not panicked on
+        let len = proof.len();
+        proof[len / 2] ^= 1;
+        let result = verify_power_proof(&params, pk.get_vk(), &instances, &proof);
+        assert!(!matches!(result, Ok(true)));
+    }
+
+    #[test]
+    fn other_transcript_test() {
+        let exp = 3;
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), OsRng);
+        let pk = keygen(&params, &TestCircuit::<Fr>::new(exp));
+
+        let base = Fr::from(2);
+        let output = compute_output(base, exp);
+        let instances = [base, output];
+        let proof = prove_batch_with_transcript::<Challenge255<_>, Keccak256Write<_, _, _>>(
+            &params,
+            &pk,
+            &[(base, output, exp)],
+            OsRng,
+        )
+        .expect("proof generation failed");
+
+        let accept = verify_power_proof_with_transcript::<Challenge255<_>, Keccak256Read<_, _, _>>(
+            &params,
+            pk.get_vk(),
+            &instances,
+            &proof,
+        );
+        assert!(accept.unwrap());
+
+        // the challenges differ, so the Blake2b verifier does not accept it
+        let result = verify_power_proof(&params, pk.get_vk(), &instances, &proof);
+        assert!(!matches!(result, Ok(true)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn verify_from_reader_test() {
+        use super::verify_power_proof_from_reader;
+        use std::io::{BufReader, Cursor, Seek, Write};
+
+        let exp = 3;
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), OsRng);
+
+        let circuit = TestCircuit::<Fr>::new(exp);
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+
+        let base = Fr::from(2);
+        let output = compute_output(base, exp);
+        let proof = prove_batch(&params, &pk, &[(base, output, exp)], OsRng)
+            .expect("proof generation failed");
+        let instances = [base, output];
+
+        let reader = Cursor::new(&proof);
+        assert!(verify_power_proof_from_reader(&params, pk.get_vk(), &instances, reader).unwrap());
+
+        // the raw bytes, not `save_proof`, which gzips them with the `compress` feature
+        let mut file = tempfile::tempfile().expect("Failed to create temp file");
+        file.write_all(&proof).expect("Failed to write proof");
+        file.rewind().expect("Failed to rewind proof");
+        let reader = BufReader::new(file);
+        assert!(verify_power_proof_from_reader(&params, pk.get_vk(), &instances, reader).unwrap());
+    }
+
+    fn keygen<C: Circuit<Fr>>(params: &ParamsKZG<Bn256>, circuit: &C) -> ProvingKey<G1Affine> {
+        let vk = keygen_vk(params, circuit).expect("keygen_vk failed");
+        keygen_pk(params, vk, circuit).expect("keygen_pk failed")
+    }
+
+    fn prove<C: Circuit<Fr>>(
+        params: &ParamsKZG<Bn256>,
+        pk: &ProvingKey<G1Affine>,
+        circuit: C,
+        instances: &[Fr],
+    ) -> Vec<u8> {
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof::<KZGCommitmentScheme<_>, ProverGWC<_>, _, _, _, _>(
+            params,
+            pk,
+            &[circuit],
+            &[&[instances]],
+            OsRng,
+            &mut transcript,
+        )
+        .expect("proof generation failed");
+        transcript.finalize()
+    }
+
+    #[test]
+    fn batch_verify_test() {
+        // both circuits fit in 16 rows, so they share the params
+        let params = ParamsKZG::<Bn256>::setup(4, OsRng);
+
+        let power_pk = keygen(&params, &TestCircuit::<Fr>::new(3));
+        let power_instances = [Fr::from(2), Fr::from(8)];
+        let power_proof = prove(&params, &power_pk, TestCircuit::new(3), &power_instances);
+
+        let fib_pk = keygen(&params, &FibonacciCircuit::<Fr>::new(9));
+        let fib_instances = [Fr::from(34)];
+        let mut fib_proof = prove(&params, &fib_pk, FibonacciCircuit::new(9), &fib_instances);
+
+        let statements = [
+            (power_pk.get_vk(), &power_instances[..], &power_proof[..]),
+            (fib_pk.get_vk(), &fib_instances[..], &fib_proof[..]),
+        ];
+        assert!(batch_verify(&params, &statements).unwrap());
+
+        // corrupting one proof fails the whole batch
+        let len = fib_proof.len();
+        fib_proof[len / 2] ^= 1;
+        let statements = [
+            (power_pk.get_vk(), &power_instances[..], &power_proof[..]),
+            (fib_pk.get_vk(), &fib_instances[..], &fib_proof[..]),
+        ];
+        assert!(!matches!(batch_verify(&params, &statements), Ok(true)));
+    }
+
+    #[test]
+    fn verify_batch_test() {
+        let exp = 3;
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), OsRng);
+        let pk = keygen(&params, &TestCircuit::<Fr>::new(exp));
+
+        let statements: Vec<(Fr, Fr)> = (2..6)
+            .map(|base| (Fr::from(base), compute_output(Fr::from(base), exp)))
+            .collect();
+        let mut proofs: Vec<Vec<u8>> = statements
+            .iter()
+            .map(|&(base, output)| {
+                prove_batch(&params, &pk, &[(base, output, exp)], OsRng)
+                    .expect("proof generation failed")
+            })
+            .collect();
+        assert!(verify_batch(&params, pk.get_vk(), &statements, &proofs));
+
+        // a proof swapped for another statement's fails the whole batch
+        proofs.swap(0, 1);
+        assert!(!verify_batch(&params, pk.get_vk(), &statements, &proofs));
+        proofs.swap(0, 1);
+
+        // so does a single corrupted proof, or a missing one
+        let len = proofs[2].len();
+        proofs[2][len / 2] ^= 1;
+        assert!(!verify_batch(&params, pk.get_vk(), &statements, &proofs));
+        assert!(!verify_batch(
+            &params,
+            pk.get_vk(),
+            &statements[..3],
+            &proofs
+        ));
+    }
+}
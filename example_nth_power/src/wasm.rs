@@ -0,0 +1,86 @@
+// Browser bindings for the example2 power circuit, built with
+// `cargo build --lib --target wasm32-unknown-unknown --features wasm`.
+// They go through IPA: its params are derived from k alone, so the page needs neither a
+// setup file nor the same randomness on both sides, and the keys are regenerated from exp.
+// The exponent is structural, so `prove_power` prefixes the proof with it as 4 little-endian
+// bytes for `verify_power` to rebuild the same circuit.
+use crate::example2::{compute_output, min_k, TestCircuit};
+use crate::example_ipa::{prove_ipa, verify_ipa};
+use crate::parse::fp_from_str;
+use halo2::{
+    halo2curves::pasta::EqAffine,
+    plonk::{keygen_pk, keygen_vk, ProvingKey},
+    poly::{commitment::ParamsProver, ipa::commitment::ParamsIPA},
+};
+use rand::rngs::OsRng;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+// Keygen runs in the page for every call, so the exponent read off a proof is bounded.
+pub const MAX_EXP: usize = 1 << 12;
+
+fn keygen(exp: usize) -> (ParamsIPA<EqAffine>, ProvingKey<EqAffine>) {
+    let params = ParamsIPA::<EqAffine>::new(min_k(exp));
+    let circuit = TestCircuit::new(exp);
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk failed");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk failed");
+    (params, pk)
+}
+
+// `base` is a decimal or 0x-prefixed hex pasta Fp element. Panics on an invalid one, or an
+// exponent above MAX_EXP.
+#[wasm_bindgen]
+pub fn prove_power(base: &str, exp: u32) -> Vec<u8> {
+    let base = fp_from_str(base).expect("invalid base");
+    let exp = exp as usize;
+    assert!(exp <= MAX_EXP, "exponent {} is above {}", exp, MAX_EXP);
+    let (params, pk) = keygen(exp);
+
+    let proof = prove_ipa(&params, &pk, base, compute_output(base, exp), exp, OsRng)
+        .expect("proof generation failed");
+
+    let mut bytes = (exp as u32).to_le_bytes().to_vec();
+    bytes.extend(proof);
+    bytes
+}
+
+// False for anything but a valid proof of base^exp = output, including unparsable input.
+#[wasm_bindgen]
+pub fn verify_power(proof: &[u8], base: &str, output: &str) -> bool {
+    let (Ok(base), Ok(output)) = (fp_from_str(base), fp_from_str(output)) else {
+        return false;
+    };
+    if proof.len() < 4 {
+        return false;
+    }
+    let (exp, proof) = proof.split_at(4);
+    let exp = u32::from_le_bytes(exp.try_into().unwrap()) as usize;
+    if exp > MAX_EXP {
+        return false;
+    }
+
+    let (params, pk) = keygen(exp);
+    matches!(
+        verify_ipa(&params, pk.get_vk(), &[base, output], proof),
+        Ok(true)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prove_power, verify_power};
+
+    #[test]
+    fn wasm_prove_verify_test() {
+        let proof = prove_power("2", 3);
+        assert!(verify_power(&proof, "2", "8"));
+
+        assert!(!verify_power(&proof, "2", "9"));
+        assert!(!verify_power(&proof, "2", "eight"));
+        assert!(!verify_power(&proof[..2], "2", "8"));
+
+        // an exponent no page should run keygen for
+        let mut forged = proof.clone();
+        forged[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(!verify_power(&forged, "2", "8"));
+    }
+}
@@ -0,0 +1,56 @@
+// Prover to verifier handoff through files only.
+// The prover writes params, keys and the proof to a temp dir and is dropped; the verifier
+// reloads params, vk and proof from disk, as a separate process would, and verifies.
+// The statement is read by `TestParams::from_env`, 2^3 = 8 unless overridden.
+use example::example2::{min_k, TestCircuit};
+use example::persistence::{
+    load_params, load_pk, load_proof, load_vk, save_params, save_pk, save_proof, save_vk,
+};
+use example::prove::{keygen_power, prove_batch};
+use example::test_params::TestParams;
+use example::verify::verify_power_proof;
+use halo2::{
+    halo2curves::bn256::{Bn256, Fr},
+    poly::kzg::commitment::ParamsKZG,
+};
+use rand::rngs::OsRng;
+
+#[test]
+fn prover_verifier_handoff_test() {
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let params_path = dir.path().join("params");
+    let pk_path = dir.path().join("pk");
+    let vk_path = dir.path().join("vk");
+    let proof_path = dir.path().join("proof");
+
+    let TestParams { base, exp, output } = TestParams::from_env();
+    let instances = [base, output];
+
+    // prover side: every handle is dropped at the end of the scope
+    {
+        let params = ParamsKZG::<Bn256>::setup(min_k(exp), OsRng);
+        let pk = keygen_power(&params, exp).expect("keygen failed");
+
+        save_params(&params_path, &params).expect("Failed to write params");
+        save_pk(&pk_path, &pk).expect("Failed to write pk");
+        save_vk(&vk_path, pk.get_vk()).expect("Failed to write vk");
+
+        let proof = prove_batch(&params, &pk, &[(base, instances[1], exp)], OsRng)
+            .expect("proof generation failed");
+        save_proof(&proof_path, &proof).expect("Failed to write proof");
+    }
+
+    // verifier side: params, vk and proof only
+    let params = load_params(&params_path).expect("Failed to read params");
+    let vk = load_vk::<TestCircuit<Fr>>(&vk_path).expect("Failed to read vk");
+    let proof = load_proof(&proof_path).expect("Failed to read proof");
+    assert!(verify_power_proof(&params, &vk, &instances, &proof).unwrap());
+
+    // the reloaded pk still proves against the reloaded params
+    let pk = load_pk::<TestCircuit<Fr>>(&pk_path).expect("Failed to read pk");
+    let proof = prove_batch(&params, &pk, &[(base, instances[1], exp)], OsRng)
+        .expect("proof generation failed");
+    assert!(verify_power_proof(&params, &vk, &instances, &proof).unwrap());
+
+    dir.close().expect("Failed to remove temp dir");
+}